@@ -0,0 +1,886 @@
+use crate::data::{Absence, AbsenceKind, DayPortion, Meeting, MeetingRule};
+use crate::{jira, time};
+use anyhow::Context;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use ical::parser::ical::component::IcalEvent;
+use ical::property::Property;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+#[derive(Debug, Clone)]
+pub enum EventTime {
+    /// All-day value, VALUE=DATE
+    Date(NaiveDate),
+    Time(DateTime<Tz>),
+}
+
+impl EventTime {
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Self::Date(date) => *date,
+            Self::Time(time) => time.date_naive(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub organizer: String,
+    pub categories: Vec<String>,
+    pub start: EventTime,
+    pub end: Option<EventTime>,
+    /// Timezone of DTSTART, recurrences are expanded in it so they keep their local time across DST
+    pub start_tz: Tz,
+    pub cancelled: bool,
+    pub rrule: Option<String>,
+    pub exdates: Vec<NaiveDate>,
+    /// Set on edited occurrences of a recurring event, replaces the occurrence on that day
+    pub recurrence_id: Option<NaiveDate>,
+}
+
+impl CalendarEvent {
+    /// Days covered by the event, DTEND of all-day events is exclusive
+    pub fn days(&self) -> Vec<NaiveDate> {
+        let first = self.start.date();
+        let last = match (&self.start, &self.end) {
+            (EventTime::Date(_), Some(EventTime::Date(end))) => *end - Duration::days(1),
+            (_, Some(end)) => end.date(),
+            (_, None) => first,
+        };
+
+        first.iter_days().take_while(|d| *d <= last.max(first)).collect()
+    }
+}
+
+/// Reads all VEVENTs from an .ics file, times are converted to `tz`
+pub fn read_events(path: &Path, tz: Tz) -> anyhow::Result<Vec<CalendarEvent>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+    parse_events(BufReader::new(file), tz).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn parse_events<B: BufRead>(reader: B, tz: Tz) -> anyhow::Result<Vec<CalendarEvent>> {
+    let mut res = vec![];
+
+    for calendar in ical::IcalParser::new(reader) {
+        let calendar = calendar?;
+
+        for event in calendar.events {
+            match parse_event(&event, tz) {
+                Some(event) => res.push(event),
+                None => continue,
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+fn parse_event(event: &IcalEvent, tz: Tz) -> Option<CalendarEvent> {
+    let prop = |name: &str| event.properties.iter().find(|p| p.name == name);
+
+    let start = parse_event_time(prop("DTSTART")?, tz)?;
+    let start_tz = event_timezone(prop("DTSTART")?, tz);
+    let end = prop("DTEND").and_then(|p| parse_event_time(p, tz));
+    let text = |name: &str| {
+        prop(name)
+            .and_then(|p| p.value.as_deref())
+            .map(unescape)
+            .unwrap_or_default()
+    };
+    let categories = event
+        .properties
+        .iter()
+        .filter(|p| p.name == "CATEGORIES")
+        .filter_map(|p| p.value.as_deref())
+        .flat_map(|v| v.split(','))
+        .map(|c| unescape(c.trim()))
+        .collect();
+
+    let exdates = event
+        .properties
+        .iter()
+        .filter(|p| p.name == "EXDATE")
+        .flat_map(|p| {
+            p.value
+                .as_deref()
+                .unwrap_or("")
+                .split(',')
+                .filter_map(|v| parse_event_time(&with_value(p, v), tz))
+                .map(|t| t.date())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    Some(CalendarEvent {
+        uid: text("UID"),
+        summary: text("SUMMARY"),
+        description: text("DESCRIPTION"),
+        organizer: text("ORGANIZER").trim_start_matches("mailto:").to_string(),
+        categories,
+        start,
+        end,
+        start_tz,
+        cancelled: text("STATUS").eq_ignore_ascii_case("CANCELLED"),
+        rrule: prop("RRULE").and_then(|p| p.value.clone()),
+        exdates,
+        recurrence_id: prop("RECURRENCE-ID")
+            .and_then(|p| parse_event_time(p, tz))
+            .map(|t| t.date()),
+    })
+}
+
+fn with_value(prop: &Property, value: &str) -> Property {
+    Property {
+        name: prop.name.clone(),
+        params: prop.params.clone(),
+        value: Some(value.to_string()),
+    }
+}
+
+fn param<'a>(prop: &'a Property, name: &str) -> Option<&'a str> {
+    prop.params
+        .as_ref()?
+        .iter()
+        .find(|(key, _)| key == name)
+        .and_then(|(_, values)| values.first())
+        .map(|v| v.as_str())
+}
+
+/// Timezone a time property is written in: UTC, its TZID or `tz` for floating times
+fn event_timezone(prop: &Property, tz: Tz) -> Tz {
+    if prop.value.as_deref().is_some_and(|v| v.trim().ends_with('Z')) {
+        return chrono_tz::UTC;
+    }
+
+    param(prop, "TZID")
+        .and_then(|id| id.trim_matches('"').parse::<Tz>().ok())
+        .unwrap_or(tz)
+}
+
+/// Handles dates, UTC times, times with a TZID and floating times (taken as `tz`)
+fn parse_event_time(prop: &Property, tz: Tz) -> Option<EventTime> {
+    let value = prop.value.as_deref()?.trim();
+
+    if param(prop, "VALUE") == Some("DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(EventTime::Date);
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(EventTime::Time(Utc.from_utc_datetime(&naive).with_timezone(&tz)));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let local = time::local_datetime(event_timezone(prop, tz), naive.date(), naive.time());
+    Some(EventTime::Time(local.with_timezone(&tz)))
+}
+
+fn unescape(val: &str) -> String {
+    val.replace("\\n", "\n")
+        .replace("\\N", "\n")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AbsenceClass {
+    Absence(AbsenceKind),
+    Holiday,
+}
+
+const SICK_WORDS: [&str; 4] = ["sick", "illness", "medical", "doctor"];
+const HOLIDAY_WORDS: [&str; 1] = ["holiday"];
+// A bare "leave" is too often the verb, as in "leave early"
+const PTO_WORDS: [&str; 10] = [
+    "pto",
+    "vacation",
+    "annual leave",
+    "paid leave",
+    "parental leave",
+    "on leave",
+    "leave day",
+    "time off",
+    "day off",
+    "ooo",
+];
+
+/// Classifies an event by its categories first, then by its summary.
+/// Words and phrases only match whole words, so "Crypto sync" isn't PTO.
+pub fn classify(event: &CalendarEvent) -> Option<AbsenceClass> {
+    let matches = |text: &str| -> Option<AbsenceClass> {
+        let text = text.to_lowercase();
+        let tokens = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>();
+        let has = |words: &[&str]| {
+            words.iter().any(|w| {
+                let phrase = w.split(' ').collect::<Vec<_>>();
+                tokens.windows(phrase.len()).any(|window| window == phrase.as_slice())
+            })
+        };
+
+        if has(&SICK_WORDS) {
+            Some(AbsenceClass::Absence(AbsenceKind::Sick))
+        } else if has(&HOLIDAY_WORDS) {
+            Some(AbsenceClass::Holiday)
+        } else if has(&PTO_WORDS) {
+            Some(AbsenceClass::Absence(AbsenceKind::Pto))
+        } else {
+            None
+        }
+    };
+
+    event
+        .categories
+        .iter()
+        .find_map(|c| matches(c))
+        .or_else(|| matches(&event.summary))
+}
+
+#[derive(Debug, Default)]
+pub struct ImportedAbsences {
+    pub absences: Vec<Absence>,
+    pub holidays: Vec<NaiveDate>,
+    pub unrecognized: Vec<String>,
+}
+
+/// Absences and holidays between `from` and `to` found in an exported calendar
+pub fn import_absences(path: &Path, from: NaiveDate, to: NaiveDate, tz: Tz) -> anyhow::Result<ImportedAbsences> {
+    Ok(absences_from_events(read_events(path, tz)?, from, to))
+}
+
+fn absences_from_events(events: Vec<CalendarEvent>, from: NaiveDate, to: NaiveDate) -> ImportedAbsences {
+    let mut res = ImportedAbsences::default();
+
+    for event in events.into_iter().filter(|e| !e.cancelled) {
+        let days = event
+            .days()
+            .into_iter()
+            .filter(|d| *d >= from && *d <= to)
+            .collect::<Vec<_>>();
+
+        if days.is_empty() {
+            continue;
+        }
+
+        let class = match classify(&event) {
+            Some(val) => val,
+            None => {
+                res.unrecognized.push(event.summary.clone());
+                continue;
+            }
+        };
+
+        // Timed events on a single day are partial absences, eg. a doctor's appointment
+        let portion = match (&event.start, &event.end) {
+            (EventTime::Time(start), Some(EventTime::Time(end))) if start.date_naive() == end.date_naive() => {
+                DayPortion::Custom(*end - *start)
+            }
+            _ => DayPortion::Full,
+        };
+
+        for date in days {
+            match class {
+                AbsenceClass::Holiday => {
+                    if !res.holidays.contains(&date) {
+                        res.holidays.push(date);
+                    }
+                }
+                AbsenceClass::Absence(kind) => {
+                    if !res.absences.iter().any(|a| a.date == date && a.kind == kind) {
+                        res.absences.push(Absence { date, kind, portion });
+                    }
+                }
+            }
+        }
+    }
+
+    res.absences.sort_by_key(|a| a.date);
+    res.holidays.sort();
+
+    res
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Subset of RFC 5545 RRULE: FREQ (DAILY, WEEKLY, MONTHLY), INTERVAL, COUNT, UNTIL and BYDAY for weekly rules,
+/// any other rule is rejected rather than expanded wrongly
+#[derive(Debug)]
+struct Recurrence {
+    freq: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDate>,
+    by_day: Vec<Weekday>,
+}
+
+impl Recurrence {
+    fn parse(rule: &str) -> Option<Recurrence> {
+        let mut res = Recurrence {
+            freq: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: vec![],
+        };
+        let mut freq = None;
+
+        for part in rule.split(';') {
+            let (key, value) = part.split_once('=')?;
+
+            match key {
+                "FREQ" => {
+                    freq = match value {
+                        "DAILY" => Some(Frequency::Daily),
+                        "WEEKLY" => Some(Frequency::Weekly),
+                        "MONTHLY" => Some(Frequency::Monthly),
+                        _ => None,
+                    }
+                }
+                "INTERVAL" => res.interval = value.parse().ok()?,
+                "COUNT" => res.count = Some(value.parse().ok()?),
+                "UNTIL" => res.until = NaiveDate::parse_from_str(value.get(0..8)?, "%Y%m%d").ok(),
+                "BYDAY" => res.by_day = value.split(',').map(parse_weekday).collect::<Option<Vec<_>>>()?,
+                // Only changes the result of rules with INTERVAL and BYDAY spanning a week boundary
+                "WKST" => {}
+                _ => return None,
+            }
+        }
+
+        res.freq = freq?;
+        if !res.by_day.is_empty() && res.freq != Frequency::Weekly {
+            return None;
+        }
+        res.interval = res.interval.max(1);
+
+        Some(res)
+    }
+
+    /// Dates of the recurrence set starting at `first`, up to and including `last`
+    fn dates(&self, first: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+        let last = match self.until {
+            Some(until) => until.min(last),
+            None => last,
+        };
+
+        let mut res = vec![];
+        let push = |date: NaiveDate, res: &mut Vec<NaiveDate>| -> bool {
+            if self.count.is_some_and(|c| res.len() as u32 >= c) || date > last {
+                return false;
+            }
+            res.push(date);
+            true
+        };
+
+        match self.freq {
+            Frequency::Daily => {
+                let mut date = first;
+                while push(date, &mut res) {
+                    date += Duration::days(self.interval as i64);
+                }
+            }
+            Frequency::Weekly => {
+                let mut days = if self.by_day.is_empty() {
+                    vec![first.weekday()]
+                } else {
+                    self.by_day.clone()
+                };
+                days.sort_by_key(|d| d.num_days_from_monday());
+
+                let mut week_start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+                'weeks: loop {
+                    for day in days.iter() {
+                        let date = week_start + Duration::days(day.num_days_from_monday() as i64);
+                        if date < first {
+                            continue;
+                        }
+                        if !push(date, &mut res) {
+                            break 'weeks;
+                        }
+                    }
+                    week_start += Duration::weeks(self.interval as i64);
+                }
+            }
+            Frequency::Monthly => {
+                let mut months = 0;
+                loop {
+                    let month0 = first.month0() + months;
+                    let year = first.year() + (month0 / 12) as i32;
+                    let date = NaiveDate::from_ymd_opt(year, month0 % 12 + 1, first.day());
+                    months += self.interval;
+
+                    match date {
+                        // Months without that day are skipped, eg. the 31st
+                        None if year <= last.year() => continue,
+                        None => break,
+                        Some(date) => {
+                            if !push(date, &mut res) {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
+
+fn parse_weekday(val: &str) -> Option<Weekday> {
+    match val {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Start and end of every occurrence of a timed event between `from` and `to`, all-day events are skipped.
+/// `None` if the event repeats by a rule that isn't supported.
+pub fn occurrences(
+    event: &CalendarEvent,
+    from: NaiveDate,
+    to: NaiveDate,
+    tz: Tz,
+) -> Option<Vec<(DateTime<Tz>, DateTime<Tz>)>> {
+    let (start, end) = match (&event.start, &event.end) {
+        (EventTime::Time(start), Some(EventTime::Time(end))) if end > start => (*start, *end),
+        _ => return Some(vec![]),
+    };
+    let duration = end - start;
+    let local_start = start.with_timezone(&event.start_tz);
+
+    // Expanded a day past `to` since the event's timezone can be ahead of `tz`
+    let dates = match event.rrule.as_deref() {
+        None => vec![local_start.date_naive()],
+        Some(rule) => Recurrence::parse(rule)?.dates(local_start.date_naive(), to + Duration::days(1)),
+    };
+
+    let res = dates
+        .into_iter()
+        .map(|d| time::local_datetime(event.start_tz, d, local_start.time()).with_timezone(&tz))
+        .filter(|started| {
+            let day = started.date_naive();
+            day >= from && day <= to && !event.exdates.contains(&day)
+        })
+        .map(|started| (started, started + duration))
+        .collect();
+
+    Some(res)
+}
+
+/// Reads a single .ics file or every .ics file in a directory
+pub fn read_events_from(path: &Path, tz: Tz) -> anyhow::Result<Vec<CalendarEvent>> {
+    if !path.is_dir() {
+        return read_events(path, tz);
+    }
+
+    let mut files = std::fs::read_dir(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ics")))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut res = vec![];
+    for file in files {
+        res.extend(read_events(&file, tz)?);
+    }
+
+    Ok(res)
+}
+
+#[derive(Debug, Default)]
+pub struct ImportedMeetings {
+    pub meetings: Vec<Meeting>,
+    pub unmatched: Vec<String>,
+    /// Recurring events with a rule that can't be expanded
+    pub unsupported: Vec<String>,
+}
+
+/// Meetings between `from` and `to`, mapped to a ticket by the first matching rule or a key in the event text
+pub fn import_meetings(
+    path: &Path,
+    from: NaiveDate,
+    to: NaiveDate,
+    tz: Tz,
+    rules: &[MeetingRule],
+) -> anyhow::Result<ImportedMeetings> {
+    Ok(meetings_from_events(read_events_from(path, tz)?, from, to, tz, rules))
+}
+
+fn meetings_from_events(
+    events: Vec<CalendarEvent>,
+    from: NaiveDate,
+    to: NaiveDate,
+    tz: Tz,
+    rules: &[MeetingRule],
+) -> ImportedMeetings {
+    let mut res = ImportedMeetings::default();
+
+    let edited = events
+        .iter()
+        .filter_map(|e| Some((e.uid.clone(), e.recurrence_id?)))
+        .collect::<Vec<_>>();
+
+    for event in events.iter().filter(|e| !e.cancelled) {
+        let occurrences = match occurrences(event, from, to, tz) {
+            Some(val) => val,
+            None => {
+                if !res.unsupported.contains(&event.summary) {
+                    res.unsupported.push(event.summary.clone());
+                }
+                continue;
+            }
+        };
+        let occurrences = occurrences
+            .into_iter()
+            .filter(|(start, _)| {
+                event.recurrence_id.is_some() || !edited.contains(&(event.uid.clone(), start.date_naive()))
+            })
+            .collect::<Vec<_>>();
+
+        if occurrences.is_empty() {
+            continue;
+        }
+
+        let ticket = rules
+            .iter()
+            .find(|r| r.matches(&event.summary, &event.organizer))
+            .map(|r| r.ticket.clone())
+            .or_else(|| jira::find_ticket_key(&event.summary))
+            .or_else(|| jira::find_ticket_key(&event.description));
+
+        let ticket = match ticket {
+            Some(val) => val,
+            None => {
+                if !res.unmatched.contains(&event.summary) {
+                    res.unmatched.push(event.summary.clone());
+                }
+                continue;
+            }
+        };
+
+        for (started, ended) in occurrences {
+            // The same event can show up in several exported calendars
+            if res
+                .meetings
+                .iter()
+                .any(|m| m.started == started && m.summary == event.summary)
+            {
+                continue;
+            }
+
+            res.meetings.push(Meeting {
+                summary: event.summary.clone(),
+                ticket: ticket.clone(),
+                started,
+                duration: ended - started,
+            });
+        }
+    }
+
+    res.meetings.sort_by_key(|m| m.started);
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:Summer vacation\r
+DTSTART;VALUE=DATE:20260914\r
+DTEND;VALUE=DATE:20260917\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Out\r
+CATEGORIES:Sick\r
+DTSTART;VALUE=DATE:20260918\r
+DTEND;VALUE=DATE:20260919\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Public Holiday\\, Bank\r
+DTSTART;VALUE=DATE:20260921\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Doctor - leave early\r
+DTSTART;TZID=Europe/Zagreb:20260922T130000\r
+DTEND;TZID=Europe/Zagreb:20260922T170000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Team lunch\r
+DTSTART:20260923T100000Z\r
+DTEND:20260923T110000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:PTO\r
+STATUS:CANCELLED\r
+DTSTART;VALUE=DATE:20260925\r
+DTEND;VALUE=DATE:20260926\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 9, d).unwrap()
+    }
+
+    fn imported(from: u32, to: u32) -> ImportedAbsences {
+        let events = parse_events(ICS.as_bytes(), chrono_tz::Europe::Zagreb).unwrap();
+        absences_from_events(events, date(from), date(to))
+    }
+
+    #[test]
+    fn all_day_events_end_exclusive() {
+        let res = imported(1, 30);
+        let pto = res
+            .absences
+            .iter()
+            .filter(|a| a.kind == AbsenceKind::Pto && a.portion == DayPortion::Full)
+            .map(|a| a.date)
+            .collect::<Vec<_>>();
+
+        assert_eq!(pto, vec![date(14), date(15), date(16)]);
+    }
+
+    #[test]
+    fn classifies_by_category_and_summary() {
+        let res = imported(1, 30);
+
+        assert!(res
+            .absences
+            .iter()
+            .any(|a| a.date == date(18) && a.kind == AbsenceKind::Sick));
+        assert_eq!(res.holidays, vec![date(21)]);
+        assert_eq!(res.unrecognized, vec!["Team lunch".to_string()]);
+    }
+
+    #[test]
+    fn only_whole_words_classify() {
+        let event = |summary: &str| CalendarEvent {
+            uid: String::new(),
+            summary: summary.to_string(),
+            description: String::new(),
+            organizer: String::new(),
+            categories: vec![],
+            start: EventTime::Date(date(1)),
+            end: None,
+            start_tz: chrono_tz::UTC,
+            cancelled: false,
+            rrule: None,
+            exdates: vec![],
+            recurrence_id: None,
+        };
+
+        assert_eq!(classify(&event("Crypto sync")), None);
+        assert_eq!(classify(&event("Symptoms review")), None);
+        assert_eq!(classify(&event("Leave room booking")), None);
+        assert_eq!(classify(&event("Bug bash before I leave")), None);
+        assert_eq!(
+            classify(&event("PTO (half day)")),
+            Some(AbsenceClass::Absence(AbsenceKind::Pto))
+        );
+        assert_eq!(
+            classify(&event("Annual leave")),
+            Some(AbsenceClass::Absence(AbsenceKind::Pto))
+        );
+        assert_eq!(
+            classify(&event("Doctor, leave early")),
+            Some(AbsenceClass::Absence(AbsenceKind::Sick))
+        );
+        assert_eq!(
+            classify(&event("Day-off")),
+            Some(AbsenceClass::Absence(AbsenceKind::Pto))
+        );
+    }
+
+    #[test]
+    fn timed_events_are_partial_absences() {
+        let res = imported(1, 30);
+        let doctor = res.absences.iter().find(|a| a.date == date(22)).unwrap();
+
+        assert_eq!(doctor.portion, DayPortion::Custom(Duration::hours(4)));
+    }
+
+    #[test]
+    fn cancelled_absences_are_skipped() {
+        let res = imported(25, 25);
+
+        assert!(res.absences.is_empty());
+        assert!(res.unrecognized.is_empty());
+    }
+
+    #[test]
+    fn only_days_in_range_are_imported() {
+        let res = imported(15, 15);
+
+        assert_eq!(res.absences.len(), 1);
+        assert_eq!(res.absences[0].date, date(15));
+        assert!(res.holidays.is_empty());
+    }
+
+    const MEETINGS_ICS: &str = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:planning\r
+SUMMARY:Sprint planning\r
+ORGANIZER;CN=Lead:mailto:lead@example.com\r
+DTSTART;TZID=Europe/Zagreb:20260902T100000\r
+DTEND;TZID=Europe/Zagreb:20260902T113000\r
+RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=WE\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:sync\r
+SUMMARY:Backend sync\r
+DESCRIPTION:Agenda\\nSee ART-42 for details\r
+DTSTART;TZID=Europe/Zagreb:20260914T140000\r
+DTEND;TZID=Europe/Zagreb:20260914T143000\r
+RRULE:FREQ=DAILY;COUNT=5\r
+EXDATE;TZID=Europe/Zagreb:20260916T140000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:sync\r
+SUMMARY:Backend sync\r
+DESCRIPTION:See ART-42\r
+RECURRENCE-ID;TZID=Europe/Zagreb:20260915T140000\r
+DTSTART;TZID=Europe/Zagreb:20260915T160000\r
+DTEND;TZID=Europe/Zagreb:20260915T170000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:coffee\r
+SUMMARY:Coffee\r
+DTSTART;TZID=Europe/Zagreb:20260914T090000\r
+DTEND;TZID=Europe/Zagreb:20260914T091500\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn meetings() -> ImportedMeetings {
+        let tz = chrono_tz::Europe::Zagreb;
+        let rules = vec![MeetingRule {
+            field: crate::data::MeetingField::Organizer,
+            pattern: "lead@".to_string(),
+            ticket: "ART-1778".to_string(),
+        }];
+        let events = parse_events(MEETINGS_ICS.as_bytes(), tz).unwrap();
+
+        meetings_from_events(events, date(1), date(30), tz, &rules)
+    }
+
+    #[test]
+    fn weekly_recurrence_with_interval() {
+        let res = meetings();
+        let planning = res
+            .meetings
+            .iter()
+            .filter(|m| m.ticket == "ART-1778")
+            .map(|m| m.started.date_naive())
+            .collect::<Vec<_>>();
+
+        assert_eq!(planning, vec![date(2), date(16), date(30)]);
+    }
+
+    #[test]
+    fn daily_recurrence_skips_exdates_and_uses_edited_occurrences() {
+        let res = meetings();
+        let sync = res
+            .meetings
+            .iter()
+            .filter(|m| m.ticket == "ART-42")
+            .map(|m| (m.started.date_naive(), m.started.time().hour(), m.duration))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            sync,
+            vec![
+                (date(14), 14, Duration::minutes(30)),
+                (date(15), 16, Duration::hours(1)),
+                (date(17), 14, Duration::minutes(30)),
+                (date(18), 14, Duration::minutes(30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn meetings_without_ticket_are_reported() {
+        let res = meetings();
+
+        assert_eq!(res.unmatched, vec!["Coffee".to_string()]);
+    }
+
+    #[test]
+    fn recurrences_keep_local_time_of_their_timezone() {
+        let ics = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:standup\r
+SUMMARY:ART-7 standup\r
+DTSTART;TZID=America/New_York:20261026T090000\r
+DTEND;TZID=America/New_York:20261026T091500\r
+RRULE:FREQ=WEEKLY;COUNT=2\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let tz = chrono_tz::Europe::Zagreb;
+        let events = parse_events(ics.as_bytes(), tz).unwrap();
+        let day = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        let res = meetings_from_events(events, day(10, 1), day(11, 30), tz, &[]);
+        let started = res
+            .meetings
+            .iter()
+            .map(|m| (m.started.date_naive(), m.started.time().hour()))
+            .collect::<Vec<_>>();
+
+        // Europe leaves DST a week before New York does
+        assert_eq!(started, vec![(day(10, 26), 14), (day(11, 2), 15)]);
+    }
+
+    #[test]
+    fn unsupported_recurrences_are_reported() {
+        assert!(Recurrence::parse("FREQ=MONTHLY;BYDAY=1MO").is_none());
+        assert!(Recurrence::parse("FREQ=DAILY;BYDAY=MO,TU").is_none());
+        assert!(Recurrence::parse("FREQ=MONTHLY;BYMONTHDAY=15").is_none());
+        assert!(Recurrence::parse("FREQ=WEEKLY;BYDAY=MO,WE;WKST=MO").is_some());
+
+        let ics = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:review\r
+SUMMARY:ART-9 review\r
+DTSTART;TZID=Europe/Zagreb:20260907T100000\r
+DTEND;TZID=Europe/Zagreb:20260907T110000\r
+RRULE:FREQ=MONTHLY;BYDAY=1MO\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let tz = chrono_tz::Europe::Zagreb;
+        let events = parse_events(ics.as_bytes(), tz).unwrap();
+        let res = meetings_from_events(events, date(1), date(30), tz, &[]);
+
+        assert!(res.meetings.is_empty());
+        assert_eq!(res.unsupported, vec!["ART-9 review".to_string()]);
+    }
+}
@@ -1,15 +1,22 @@
+mod check;
 mod configure;
 mod debug_config;
 mod delete;
+mod history;
 mod publish;
 mod repo;
+mod report;
+mod undo;
 
 use crate::data::*;
-use clap::{command, Args, Parser, Subcommand};
+use crate::time;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use clap::{Args, Parser, Subcommand};
 use colored::Colorize;
 
 type Cfg<'a> = &'a mut ConfigFile;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Parser, Debug)]
 #[command(
     author = "Domagoj",
@@ -24,6 +31,49 @@ pub enum Tempo {
     Delete(delete::DeleteCommand),
     Configure(configure::ConfigureCommand),
     Repo(repo::RepoCommand),
+    History(history::HistoryCommand),
+    Undo(undo::UndoCommand),
+    Report(report::ReportCommand),
+    Check(check::CheckCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct DateRangeArgs {
+    #[arg(long, help = "First day to include, eg. 2026-09-01")]
+    from: Option<NaiveDate>,
+    #[arg(long, requires = "from", help = "Last day to include, defaults to today")]
+    to: Option<NaiveDate>,
+    #[arg(long, conflicts_with = "from")]
+    range: Option<time::RangePreset>,
+}
+
+impl DateRangeArgs {
+    /// Uses --from/--to or --range when given, otherwise asks the user
+    pub fn resolve(&self, user_data: &UserData) -> anyhow::Result<(NaiveDateTime, NaiveDateTime)> {
+        let today = Local::now().date_naive();
+
+        if let Some(from) = self.from {
+            let to = self.to.unwrap_or(today);
+            if from > to {
+                anyhow::bail!("--from {} is after --to {}", from, to);
+            }
+
+            return Ok(time::to_datetime_range(from, to));
+        }
+
+        if let Some(preset) = self.range {
+            let (start, end) = time::preset_range(preset, today, user_data.sprint())?;
+            println!(
+                "Selected {} - {}",
+                start.to_string().bright_green(),
+                end.to_string().bright_green()
+            );
+
+            return Ok(time::to_datetime_range(start, end));
+        }
+
+        time::get_date_range_from_user(user_data.sprint())
+    }
 }
 
 macro_rules! bail_ok {
@@ -71,6 +121,10 @@ impl Tempo {
             Tempo::Configure(args) => configure::command(config, args),
             Tempo::Repo(args) => repo::command(config, args.action),
             Tempo::Delete(args) => delete::command(config, args),
+            Tempo::History(args) => history::command(config, args),
+            Tempo::Undo(args) => undo::command(config, args),
+            Tempo::Report(args) => report::command(config, args),
+            Tempo::Check(args) => check::command(config, args),
         };
 
         match res {
@@ -0,0 +1,332 @@
+use crate::jira::{self, Worklog};
+use crate::{calendar, pretty_print};
+
+use super::*;
+use chrono::{Datelike, Weekday};
+use std::path::PathBuf;
+
+/// Exit codes, so a reminder can tell what's wrong without parsing the output
+const EXIT_MISSING: i32 = 1;
+const EXIT_OVERLOGGED: i32 = 2;
+const EXIT_FAILED: i32 = 3;
+
+#[derive(Args, Debug)]
+#[command(
+    about = "Check that every day of a month has its hours logged",
+    long_about = "Check that every day of a month has its hours logged.\n\
+        Exits with 1 when hours are missing, 2 when days are only over-logged or logged on weekends and 3 when the check failed"
+)]
+pub struct CheckCommand {
+    #[arg(long, value_parser = parse_month, help = "Month to check, eg. 2026-09, the current one by default")]
+    month: Option<NaiveDate>,
+
+    #[arg(long, help = "Calendar export (.ics) to read absences and holidays from")]
+    absences: Option<PathBuf>,
+
+    #[arg(long, help = "Check today as well, by default only days that are over are checked")]
+    today: bool,
+}
+
+fn parse_month(val: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", val), "%Y-%m-%d")
+        .map_err(|_| format!("{} isn't a month, eg. 2026-09", val))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DayProblem {
+    Missing,
+    Overlogged,
+    Weekend,
+}
+
+#[derive(Debug, PartialEq)]
+struct DayCheck {
+    day: NaiveDate,
+    logged: Duration,
+    expected: Duration,
+    problem: DayProblem,
+}
+
+pub fn command(config: Cfg, args: CheckCommand) -> anyhow::Result<()> {
+    let code = match check(config, &args) {
+        Ok(code) => code,
+        Err(err) => {
+            println!("{}: {}", "Err".bright_red(), err.root_cause().to_string().bright_red());
+            EXIT_FAILED
+        }
+    };
+
+    if code != 0 {
+        config.save();
+        std::process::exit(code);
+    }
+
+    Ok(())
+}
+
+fn check(config: Cfg, args: &CheckCommand) -> anyhow::Result<i32> {
+    let today = Local::now().date_naive();
+    let first_day = args.month.unwrap_or(today.with_day(1).unwrap());
+    if first_day > today {
+        anyhow::bail!("{} hasn't started yet", first_day.format("%B %Y"));
+    }
+    // Days still to come can't be missing anything, today is usually still being logged
+    let last_checked = if args.today { today } else { today.pred_opt().unwrap() };
+    let last_day = time::last_day_of_month(first_day.year(), first_day.month()).min(last_checked);
+    if last_day < first_day {
+        println!("{}", "Nothing to check yet".bright_green());
+        return Ok(0);
+    }
+
+    let user_data = config.user_data.initialize();
+    let imported = match args.absences.as_ref().or(user_data.absences_path.as_ref()) {
+        Some(path) => calendar::import_absences(path, first_day, last_day, user_data.tz()?)?,
+        None => calendar::ImportedAbsences::default(),
+    };
+    let adjustments = DayAdjustments {
+        absences: imported.absences,
+        skip_days: imported.holidays,
+        ..Default::default()
+    };
+    let absence_tickets = user_data.get_absence_tickets().values().cloned().collect::<Vec<_>>();
+    let schedule = user_data.get_work_schedule().clone();
+
+    let client = jira::JiraClient::new(config);
+    let worklogs = client.find_worklogs(&client.myself()?, first_day, last_day)?;
+
+    let checks = check_days(
+        &worklogs,
+        first_day,
+        last_day,
+        &adjustments,
+        &schedule,
+        &absence_tickets,
+    );
+
+    let (logged, expected) = first_day
+        .iter_days()
+        .take_while(|day| *day <= last_day)
+        .map(|day| day_totals(&worklogs, &adjustments, &schedule, &absence_tickets, day))
+        .fold((Duration::zero(), Duration::zero()), |acc, (logged, expected)| {
+            (acc.0 + logged, acc.1 + expected)
+        });
+    println!(
+        "{} - {}: {}h of {}h logged",
+        first_day,
+        last_day,
+        pretty_print::format_hours(logged).bold(),
+        pretty_print::format_hours(expected).green()
+    );
+
+    for check in checks.iter() {
+        let problem = match check.problem {
+            DayProblem::Missing => "missing".bright_red(),
+            DayProblem::Overlogged => "over-logged".yellow(),
+            DayProblem::Weekend => "weekend".yellow(),
+        };
+
+        println!(
+            "  {} {:<11} {}h of {}h",
+            check.day.format("%a %Y-%m-%d"),
+            problem,
+            pretty_print::format_hours(check.logged),
+            pretty_print::format_hours(check.expected)
+        );
+    }
+
+    if checks.iter().any(|c| c.problem == DayProblem::Missing) {
+        return Ok(EXIT_MISSING);
+    }
+
+    if !checks.is_empty() {
+        return Ok(EXIT_OVERLOGGED);
+    }
+
+    println!("{}", "Every day is logged".bright_green());
+    Ok(0)
+}
+
+/// Scheduled time without holidays and absences, which is what should be logged to tickets.
+/// `logged_absence` is the time logged to absence tickets, it's the absence of days the calendar has none for
+fn expected_work(
+    adjustments: &DayAdjustments,
+    schedule: &WorkSchedule,
+    day: NaiveDate,
+    logged_absence: Duration,
+) -> Duration {
+    let capacity = adjustments.capacity(schedule, day);
+    let absent = match adjustments.absences_on(day).next() {
+        Some(_) => adjustments
+            .absences_on(day)
+            .fold(Duration::zero(), |acc, a| acc + a.portion.resolve(capacity)),
+        None => logged_absence,
+    };
+
+    (adjustments.expected(schedule, day) - absent).max(Duration::zero())
+}
+
+/// Time logged to work tickets on `day` and the time expected there
+fn day_totals(
+    worklogs: &[Worklog],
+    adjustments: &DayAdjustments,
+    schedule: &WorkSchedule,
+    absence_tickets: &[String],
+    day: NaiveDate,
+) -> (Duration, Duration) {
+    let (absence, work) = worklogs
+        .iter()
+        .filter(|w| w.started.date_naive() == day)
+        .partition::<Vec<_>, _>(|w| absence_tickets.contains(&w.issue_key));
+    let total = |worklogs: Vec<&Worklog>| worklogs.iter().fold(Duration::zero(), |acc, w| acc + w.time_spent);
+
+    (total(work), expected_work(adjustments, schedule, day, total(absence)))
+}
+
+/// Days between `first_day` and `last_day` where logged time doesn't match the schedule.
+/// Worklogs on absence tickets don't count as work, absences are taken out of the expected time instead
+fn check_days(
+    worklogs: &[Worklog],
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+    adjustments: &DayAdjustments,
+    schedule: &WorkSchedule,
+    absence_tickets: &[String],
+) -> Vec<DayCheck> {
+    first_day
+        .iter_days()
+        .take_while(|day| *day <= last_day)
+        .filter_map(|day| {
+            let (logged, expected) = day_totals(worklogs, adjustments, schedule, absence_tickets, day);
+
+            let weekend = matches!(day.weekday(), Weekday::Sat | Weekday::Sun);
+            let problem = if weekend && logged > Duration::zero() {
+                DayProblem::Weekend
+            } else if logged < expected {
+                DayProblem::Missing
+            } else if logged > expected {
+                DayProblem::Overlogged
+            } else {
+                return None;
+            };
+
+            Some(DayCheck {
+                day,
+                logged,
+                expected,
+                problem,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Absence, AbsenceKind, DayPortion};
+    use chrono::{FixedOffset, TimeZone};
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 9, d).unwrap()
+    }
+
+    fn worklog(ticket: &str, d: u32, hours: i64) -> Worklog {
+        Worklog {
+            id: String::new(),
+            issue_id: String::new(),
+            issue_key: ticket.to_string(),
+            started: FixedOffset::east_opt(7200)
+                .unwrap()
+                .with_ymd_and_hms(2026, 9, d, 9, 0, 0)
+                .unwrap(),
+            time_spent: Duration::hours(hours),
+            comment: String::new(),
+        }
+    }
+
+    #[test]
+    fn flags_missing_overlogged_and_weekend_days() {
+        // Monday the 14th to Sunday the 20th
+        let worklogs = vec![
+            worklog("ART-1", 14, 8),
+            worklog("ART-1", 15, 6),
+            worklog("ART-2", 16, 9),
+            worklog("PTO-1", 17, 8),
+            worklog("ART-1", 18, 4),
+            worklog("ART-3", 19, 1),
+        ];
+        let adjustments = DayAdjustments {
+            absences: vec![
+                Absence {
+                    date: day(17),
+                    kind: AbsenceKind::Pto,
+                    portion: DayPortion::Full,
+                },
+                Absence {
+                    date: day(18),
+                    kind: AbsenceKind::Sick,
+                    portion: DayPortion::Half,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let checks = check_days(
+            &worklogs,
+            day(14),
+            day(20),
+            &adjustments,
+            &WorkSchedule::default(),
+            &[String::from("PTO-1")],
+        );
+
+        let problems = checks.iter().map(|c| (c.day, c.problem)).collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            [
+                (day(15), DayProblem::Missing),
+                (day(16), DayProblem::Overlogged),
+                (day(19), DayProblem::Weekend)
+            ]
+        );
+        assert_eq!(checks[0].logged, Duration::hours(6));
+        assert_eq!(checks[0].expected, Duration::hours(8));
+    }
+
+    #[test]
+    fn absence_tickets_count_without_a_calendar() {
+        let worklogs = vec![
+            worklog("PTO-1", 14, 8),
+            worklog("PTO-1", 15, 4),
+            worklog("ART-1", 15, 4),
+            worklog("PTO-1", 16, 4),
+        ];
+
+        let checks = check_days(
+            &worklogs,
+            day(14),
+            day(16),
+            &DayAdjustments::default(),
+            &WorkSchedule::default(),
+            &[String::from("PTO-1")],
+        );
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].day, day(16));
+        assert_eq!(checks[0].problem, DayProblem::Missing);
+        assert_eq!(checks[0].expected, Duration::hours(4));
+    }
+
+    #[test]
+    fn holidays_expect_nothing() {
+        let adjustments = DayAdjustments {
+            skip_days: vec![day(14)],
+            ..Default::default()
+        };
+
+        let checks = check_days(&[], day(14), day(15), &adjustments, &WorkSchedule::default(), &[]);
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].day, day(15));
+        assert_eq!(checks[0].problem, DayProblem::Missing);
+    }
+}
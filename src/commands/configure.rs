@@ -1,4 +1,7 @@
 use crate::pretty_print;
+use anyhow::Context;
+use chrono::{NaiveDate, NaiveTime};
+use std::path::PathBuf;
 
 use super::*;
 
@@ -10,6 +13,54 @@ pub struct ConfigureCommand {
 
     #[arg(long)]
     jira_token: Option<String>,
+
+    #[arg(long, help = "Jira root url, eg. https://jira.example.com")]
+    jira_url: Option<String>,
+
+    #[arg(long, help = "First day of any sprint, used by --range sprint")]
+    sprint_start: Option<NaiveDate>,
+
+    #[arg(long, help = "Sprint length in weeks")]
+    sprint_weeks: Option<u32>,
+
+    #[arg(long, help = "IANA timezone for worklog start times, eg. Europe/Zagreb")]
+    timezone: Option<chrono_tz::Tz>,
+
+    #[arg(long, help = "Calendar export (.ics) with absences, used when publishing")]
+    absences_path: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Calendar export (.ics) or a directory of them with meetings, used when publishing"
+    )]
+    calendar_path: Option<PathBuf>,
+
+    #[arg(long, help = "Ticket for working time no commit covers, eg. general development")]
+    fallback_ticket: Option<String>,
+
+    #[arg(
+        long,
+        help = "How each commit is written into the worklog comment, eg. \"[{repo}@{sha}] {subject} {url}\". Available: {repo} {sha} {subject} {branch} {url}"
+    )]
+    comment_template: Option<String>,
+
+    #[arg(long, help = "Longest worklog comment in characters, longer ones are cut")]
+    max_comment_length: Option<usize>,
+
+    #[arg(long, help = "Remote commits are fetched from, origin by default")]
+    git_remote: Option<String>,
+
+    #[arg(long, help = "Branch commits are read from, develop by default")]
+    git_branch: Option<String>,
+
+    #[arg(
+        long,
+        help = "Access token for https remotes, used when no credential helper has one. Stored unencrypted in the config file"
+    )]
+    git_token: Option<String>,
+
+    #[arg(long, value_enum, help = "Which date of a commit decides the day it's logged on")]
+    commit_date: Option<CommitDate>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -18,6 +69,37 @@ pub enum ConfigureSubCommands {
     Rm,
     #[command(subcommand)]
     Alias(UserAliasArgs),
+    #[command(subcommand, about = "Configure working hours")]
+    Schedule(ScheduleArgs),
+    #[command(about = "Set the jira ticket an absence type is logged to")]
+    AbsenceTicket {
+        kind: AbsenceKind,
+        ticket: String,
+    },
+    #[command(subcommand, about = "Map calendar meetings to jira tickets")]
+    Meetings(MeetingRuleArgs),
+    #[command(subcommand, about = "Private keys for ssh remotes, tried after ssh-agent")]
+    SshKey(SshKeyArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SshKeyArgs {
+    Add { path: PathBuf },
+    Rm,
+    Ls,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MeetingRuleArgs {
+    #[command(about = "Log meetings whose title or organizer contains <pattern> to <ticket>")]
+    Add {
+        #[arg(long, value_enum, default_value = "title")]
+        field: MeetingField,
+        pattern: String,
+        ticket: String,
+    },
+    Rm,
+    Ls,
 }
 
 #[derive(Subcommand, Debug)]
@@ -27,6 +109,35 @@ pub enum UserAliasArgs {
     Ls,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum ScheduleArgs {
+    #[command(about = "Set the regular work week")]
+    Set {
+        #[arg(long, help = "Hours per weekday, eg. 8,8,8,8,8,0,0", value_parser = parse_week_arg)]
+        week: Option<WeekMinutes>,
+        #[arg(long, help = "Start of the working day, eg. 09:00")]
+        start: Option<NaiveTime>,
+        #[arg(
+            long,
+            help = "Daily standup length in minutes",
+            value_parser = clap::value_parser!(i64).range(0..=1440)
+        )]
+        standup: Option<i64>,
+    },
+    #[command(about = "Use a different week between two dates, eg. part-time periods or half-days")]
+    Override {
+        #[arg(long)]
+        from: NaiveDate,
+        #[arg(long)]
+        to: NaiveDate,
+        #[arg(long, help = "Hours per weekday, eg. 4,4,4,4,4,0,0", value_parser = parse_week_arg)]
+        week: WeekMinutes,
+    },
+    #[command(about = "Remove schedule override(s)")]
+    RmOverride,
+    Ls,
+}
+
 #[derive(Args, Debug)]
 pub struct CommandArgs {
     val: String,
@@ -36,8 +147,8 @@ pub fn command(config: Cfg, args: ConfigureCommand) -> anyhow::Result<()> {
     use ConfigureSubCommands::*;
     config.user_data.initialize();
 
-    if args.action.is_some() {
-        match args.action.unwrap() {
+    if let Some(action) = args.action {
+        match action {
             Ls => {
                 print_user_data(config.user_data.inner());
                 bail_ok!();
@@ -47,6 +158,15 @@ pub fn command(config: Cfg, args: ConfigureCommand) -> anyhow::Result<()> {
                 bail_ok!("Deleted all user data");
             }
             Alias(args) => handle_alias(config, args)?,
+            Schedule(args) => handle_schedule(config, args)?,
+            AbsenceTicket { kind, ticket } => {
+                let tickets = config.user_data.inner_mut().absence_tickets.as_mut().unwrap();
+                print_confirm(&kind.to_string(), &ticket);
+                tickets.insert(kind, ticket);
+                bail_ok!();
+            }
+            Meetings(args) => handle_meeting_rules(config, args)?,
+            SshKey(args) => handle_ssh_keys(config, args)?,
         };
     }
     let user_data = config.user_data.inner_mut();
@@ -56,6 +176,76 @@ pub fn command(config: Cfg, args: ConfigureCommand) -> anyhow::Result<()> {
         user_data.set_jira_token(v);
     }
 
+    if let Some(v) = args.jira_url {
+        let v = v.trim_end_matches('/').to_string();
+        print_confirm("jira-url", &v);
+        user_data.set_jira_url(v);
+    }
+
+    if let Some(v) = args.sprint_start {
+        print_confirm("sprint-start", &v.to_string());
+        user_data.set_sprint_start(v);
+    }
+
+    if let Some(v) = args.sprint_weeks {
+        print_confirm("sprint-weeks", &v.to_string());
+        user_data.set_sprint_weeks(v);
+    }
+
+    if let Some(v) = args.timezone {
+        print_confirm("timezone", v.name());
+        user_data.set_timezone(v.name().to_string());
+    }
+
+    if let Some(v) = args.absences_path {
+        let full_path = std::fs::canonicalize(&v).with_context(|| format!("Invalid path {}", v.display()))?;
+        print_confirm("absences-path", &full_path.to_string_lossy());
+        user_data.set_absences_path(full_path);
+    }
+
+    if let Some(v) = args.calendar_path {
+        let full_path = std::fs::canonicalize(&v).with_context(|| format!("Invalid path {}", v.display()))?;
+        print_confirm("calendar-path", &full_path.to_string_lossy());
+        user_data.set_calendar_path(full_path);
+    }
+
+    if let Some(v) = args.fallback_ticket {
+        let v = v.trim().to_uppercase();
+        print_confirm("fallback-ticket", &v);
+        user_data.set_fallback_ticket(v);
+    }
+
+    if let Some(v) = args.comment_template {
+        validate_template(&v)?;
+        print_confirm("comment-template", &v);
+        user_data.comment_format.as_mut().unwrap().template = v;
+    }
+
+    if let Some(v) = args.max_comment_length {
+        print_confirm("max-comment-length", &v.to_string());
+        user_data.comment_format.as_mut().unwrap().max_length = v;
+    }
+
+    if let Some(v) = args.git_remote {
+        print_confirm("git-remote", &v);
+        user_data.set_git_remote(v);
+    }
+
+    if let Some(v) = args.git_branch {
+        print_confirm("git-branch", &v);
+        user_data.set_git_branch(v);
+    }
+
+    if let Some(v) = args.git_token {
+        print_confirm("git-token", &mask_secret(&v));
+        user_data.set_git_token(v);
+    }
+
+    if let Some(v) = args.commit_date {
+        print_confirm("commit-date", &format!("{:?}", v).to_lowercase());
+        user_data.set_commit_date(v);
+    }
+
     Ok(())
 }
 
@@ -63,10 +253,66 @@ fn print_confirm(field: &str, value: &str) {
     println!("Succesfully saved: {} to: {}", field.bright_green(), value.green())
 }
 
+/// Only the last 4 characters of long values, so secrets don't end up in the terminal scrollback
+fn mask_secret(value: &str) -> String {
+    let chars = value.chars().collect::<Vec<_>>();
+    if chars.len() <= 8 {
+        return String::from("****");
+    }
+
+    format!("****{}", chars[chars.len() - 4..].iter().collect::<String>())
+}
+
 fn print_user_data(user_data: &UserData) {
     println!("{:#?}", &user_data);
 }
 
+fn handle_ssh_keys(cfg: Cfg, args: SshKeyArgs) -> anyhow::Result<()> {
+    let keys = cfg.user_data.inner_mut().ssh_keys.as_mut().unwrap();
+
+    match args {
+        SshKeyArgs::Add { path } => {
+            let full_path = std::fs::canonicalize(&path).with_context(|| format!("Invalid path {}", path.display()))?;
+            if keys.contains(&full_path) {
+                bail_ok!("{} Already added", full_path.display().to_string().bright_green());
+            }
+
+            keys.push(full_path);
+            bail_ok!("Added {}", keys[keys.len() - 1].display().to_string().bright_green());
+        }
+        SshKeyArgs::Rm => {
+            if keys.is_empty() {
+                bail_ok!("No keys configured");
+            }
+
+            let labels = keys.iter().map(|k| k.display().to_string()).collect::<Vec<_>>();
+            let res = match inquire::MultiSelect::new("Delete key(s)", labels).raw_prompt() {
+                Ok(val) => val.into_iter().map(|o| o.index).collect::<Vec<_>>(),
+                Err(_) => bail_ok!(),
+            };
+
+            let mut idx = 0;
+            keys.retain(|_| {
+                let keep = !res.contains(&idx);
+                idx += 1;
+                keep
+            });
+            bail_ok!("{}", "Removed keys".red());
+        }
+        SshKeyArgs::Ls => {
+            if keys.is_empty() {
+                bail_ok!("No keys configured, trying ~/.ssh/id_ed25519, id_ecdsa and id_rsa");
+            }
+
+            for key in keys.iter() {
+                println!("  {}", key.display().to_string().bright_green());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn handle_alias(cfg: Cfg, args: UserAliasArgs) -> anyhow::Result<()> {
     match args {
         UserAliasArgs::Add { val } => {
@@ -112,3 +358,113 @@ fn handle_alias(cfg: Cfg, args: UserAliasArgs) -> anyhow::Result<()> {
 
     return Ok(());
 }
+
+fn parse_week_arg(val: &str) -> Result<WeekMinutes, String> {
+    parse_week(val).map_err(|e| e.to_string())
+}
+
+fn print_schedule(schedule: &WorkSchedule) {
+    println!("Week: {}", format_week(&schedule.week).bright_green());
+    println!("Start time: {}", schedule.start_time.to_string().bright_green());
+    println!("Standup: {}min", schedule.standup_minutes.to_string().bright_green());
+
+    for (idx, o) in schedule.overrides.iter().enumerate() {
+        pretty_print::print_row(idx, &format!("{} - {}: {}", o.from, o.to, format_week(&o.week)));
+    }
+}
+
+fn handle_schedule(cfg: Cfg, args: ScheduleArgs) -> anyhow::Result<()> {
+    let schedule = cfg.user_data.inner_mut().work_schedule.as_mut().unwrap();
+
+    match args {
+        ScheduleArgs::Set { week, start, standup } => {
+            if let Some(week) = week {
+                schedule.week = week;
+            }
+            if let Some(start) = start {
+                schedule.start_time = start;
+            }
+            if let Some(standup) = standup {
+                schedule.standup_minutes = standup;
+            }
+
+            print_schedule(schedule);
+        }
+        ScheduleArgs::Override { from, to, week } => {
+            if from > to {
+                bail_ok!("{}", "--from must be before --to".red());
+            }
+
+            schedule.overrides.push(ScheduleOverride { from, to, week });
+            bail_ok!(
+                "Added override {} - {}: {}",
+                from,
+                to,
+                format_week(&week).bright_green()
+            );
+        }
+        ScheduleArgs::RmOverride => {
+            if schedule.overrides.is_empty() {
+                bail_ok!("No overrides configured");
+            }
+
+            let options = schedule
+                .overrides
+                .iter()
+                .map(|o| format!("{} - {}: {}", o.from, o.to, format_week(&o.week)))
+                .collect::<Vec<_>>();
+
+            let res = match inquire::MultiSelect::new("Delete override(s)", options.clone()).prompt() {
+                Ok(val) => val,
+                Err(_) => bail_ok!(),
+            };
+
+            let mut idx = 0;
+            schedule.overrides.retain(|_| {
+                let keep = !res.contains(&options[idx]);
+                idx += 1;
+                keep
+            });
+        }
+        ScheduleArgs::Ls => print_schedule(schedule),
+    }
+
+    return Ok(());
+}
+
+fn handle_meeting_rules(cfg: Cfg, args: MeetingRuleArgs) -> anyhow::Result<()> {
+    let rules = cfg.user_data.inner_mut().meeting_rules.as_mut().unwrap();
+
+    match args {
+        MeetingRuleArgs::Add { field, pattern, ticket } => {
+            let rule = MeetingRule { field, pattern, ticket };
+            println!("Added {}", rule.to_string().bright_green());
+            rules.push(rule);
+        }
+        MeetingRuleArgs::Rm => {
+            if rules.is_empty() {
+                bail_ok!("No meeting rules configured");
+            }
+
+            let options = rules.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+            let res = match inquire::MultiSelect::new("Delete rule(s)", options.clone()).prompt() {
+                Ok(val) => val,
+                Err(_) => bail_ok!(),
+            };
+
+            let mut idx = 0;
+            rules.retain(|_| {
+                let keep = !res.contains(&options[idx]);
+                idx += 1;
+                keep
+            });
+        }
+        MeetingRuleArgs::Ls => {
+            for (idx, rule) in rules.iter().enumerate() {
+                pretty_print::print_row(idx, &rule.to_string());
+            }
+        }
+    }
+
+    return Ok(());
+}
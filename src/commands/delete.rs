@@ -1,17 +1,65 @@
-use crate::{jira, time};
+use crate::{jira, pretty_print};
 
 use super::*;
 
 #[derive(Args, Debug)]
-#[command(about = "Delete all time entries from specified month")]
-pub struct DeleteCommand {}
+#[command(about = "Delete all time entries in the specified range")]
+pub struct DeleteCommand {
+    #[command(flatten)]
+    range: DateRangeArgs,
+}
 
-pub fn command(config: Cfg, _args: DeleteCommand) -> anyhow::Result<()> {
-    let (start_date, end_date) = time::get_date_range_from_user()?;
+pub fn command(config: Cfg, args: DeleteCommand) -> anyhow::Result<()> {
+    let (start_date, end_date) = args.range.resolve(config.user_data.initialize())?;
 
     println!("Deleting isues...");
-    jira::delete_worklogs(config, start_date, end_date)?;
+    let client = jira::JiraClient::new(config);
+    delete_worklogs(&client, start_date.date(), end_date.date())?;
     print!("Done");
 
     return Ok(());
 }
+
+fn delete_worklogs(client: &jira::JiraClient, start: NaiveDate, end: NaiveDate) -> anyhow::Result<()> {
+    let me = client.myself()?;
+    let worklogs = client.find_worklogs(&me, start, end)?;
+
+    let total_time_spent = worklogs.iter().fold(Duration::zero(), |acc, w| acc + w.time_spent);
+    println!("Total time spent: {}", pretty_print::format_hours(total_time_spent));
+
+    let pb = pretty_print::get_progress_bar(worklogs.len() as u64);
+
+    for worklog in worklogs {
+        pb.inc(1);
+        let response = client.delete_worklog(&worklog.issue_id, &worklog.id)?;
+
+        if !response.status().is_success() {
+            pb.println(format!("Failed to delete worklog: {}", response.text()?));
+        }
+    }
+    pb.finish_and_clear();
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockJira, MOCK_USER};
+
+    #[test]
+    fn deletes_only_own_worklogs_in_range() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        jira.add_worklog("ART-1", MOCK_USER, "2026-09-14T09:00:00.000+0200", 3600);
+        jira.add_worklog("ART-1", MOCK_USER, "2026-09-30T09:00:00.000+0200", 3600);
+        let before = jira.add_worklog("ART-1", MOCK_USER, "2026-08-31T09:00:00.000+0200", 3600);
+        let other = jira.add_worklog("ART-1", "someone", "2026-09-14T09:00:00.000+0200", 3600);
+
+        let day = |d| NaiveDate::from_ymd_opt(2026, 9, d).unwrap();
+        delete_worklogs(&jira.client(), day(1), day(30)).unwrap();
+
+        let left = jira.worklogs().into_iter().map(|w| w.id).collect::<Vec<_>>();
+        assert_eq!(left, vec![before, other]);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::pretty_print;
+
+use super::*;
+
+#[derive(Args, Debug)]
+#[command(about = "List past publish runs")]
+pub struct HistoryCommand {
+    #[command(subcommand)]
+    action: Option<HistoryAction>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryAction {
+    #[command(about = "List all runs")]
+    Ls,
+    #[command(about = "Show every entry of a run, the latest one by default")]
+    Show { id: Option<String> },
+}
+
+pub fn command(_config: Cfg, args: HistoryCommand) -> anyhow::Result<()> {
+    match args.action.unwrap_or(HistoryAction::Ls) {
+        HistoryAction::Ls => {
+            let reports = PublishReport::list()?;
+            if reports.is_empty() {
+                bail_ok!("Nothing published yet");
+            }
+
+            for report in reports {
+                println!(
+                    "  {}  {} - {}  created: {} ({}h)  failed: {}  unknown: {}  skipped: {}  deleted: {}",
+                    report.id.bright_green(),
+                    report.from,
+                    report.to,
+                    report.count(EntryStatus::Created),
+                    pretty_print::format_hours(report.time_spent(EntryStatus::Created)),
+                    report.count(EntryStatus::Failed),
+                    report.count(EntryStatus::Unknown),
+                    report.count(EntryStatus::Skipped),
+                    report.count(EntryStatus::Deleted),
+                );
+            }
+        }
+        HistoryAction::Show { id } => {
+            let report = match id {
+                Some(id) => PublishReport::load(&id)?,
+                None => match PublishReport::list()?.pop() {
+                    Some(val) => val,
+                    None => {
+                        bail_ok!("Nothing published yet");
+                    }
+                },
+            };
+
+            println!(
+                "Run {} ({} - {}), published at {}",
+                report.id.bright_green(),
+                report.from,
+                report.to,
+                report.created_at.format("%Y-%m-%d %H:%M")
+            );
+
+            for entry in report.entries.iter() {
+                let status = match entry.status {
+                    EntryStatus::Created => "created".green(),
+                    EntryStatus::Skipped => "skipped".yellow(),
+                    EntryStatus::Failed => "failed".bright_red(),
+                    EntryStatus::Deleted => "deleted".bright_black(),
+                    EntryStatus::Unknown => "unknown".bright_red(),
+                    EntryStatus::Pending => "pending".yellow(),
+                };
+
+                println!(
+                    "  {} {} {:>5}h {:<12} {} {}",
+                    status,
+                    entry.started.format("%Y-%m-%d %H:%M"),
+                    pretty_print::format_hours(entry.time_spent()),
+                    entry.ticket,
+                    entry.http_status.map(|s| s.to_string()).unwrap_or_default(),
+                    entry
+                        .worklog_id
+                        .as_deref()
+                        .map(|id| format!("worklog {}", id))
+                        .unwrap_or_default(),
+                );
+
+                if let Some(err) = entry.error.as_deref() {
+                    println!("      {}", err.bright_red());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
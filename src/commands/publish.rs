@@ -1,118 +1,563 @@
+mod editor;
+
+use crate::git_helpers::{self, GitSync};
+use crate::planning::{self, PlanCalendar};
 use crate::{jira, pretty_print, step::Step, time};
 
 use super::*;
-use anyhow::bail;
-use chrono::Duration;
+use crate::calendar::{self, ImportedAbsences};
+use crate::data::Meeting;
+use anyhow::{bail, Context};
+use chrono::{Duration, NaiveDate};
+use std::path::PathBuf;
+use std::sync::Mutex;
 
 #[derive(Args, Debug)]
 #[command(about = "Publish hours to jira time tracking")]
 pub struct PublishCommand {
-    #[arg(short = 's', long)]
-    skip_pull: bool,
+    #[arg(
+        short = 's',
+        long,
+        alias = "skip-pull",
+        help = "Don't fetch, read commits fetched before"
+    )]
+    skip_fetch: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "skip_fetch",
+        help = "Merge the remote branch into the local one and check it out, instead of only fetching"
+    )]
+    pull: bool,
+
+    #[arg(
+        long,
+        default_value_t = 60,
+        help = "Seconds to wait for repos to fetch before giving up on them"
+    )]
+    fetch_timeout: u64,
+
+    #[arg(
+        long,
+        help = "Walk the history of every repo again instead of reading the commit cache"
+    )]
+    no_cache: bool,
+
+    #[arg(long, default_value_t = 4, help = "Number of worklogs sent to jira at the same time")]
+    concurrency: usize,
+
+    #[command(flatten)]
+    range: DateRangeArgs,
+
+    #[arg(long = "day", help = "Hours worked on a day, eg. --day 2026-09-12=4h", value_parser = parse_day_arg)]
+    days: Vec<HoursOverride>,
+
+    #[arg(long, help = "Calendar export (.ics) to read absences and holidays from")]
+    absences: Option<PathBuf>,
+
+    #[arg(long, help = "Calendar export (.ics) or a directory of them to read meetings from")]
+    calendar: Option<PathBuf>,
+}
+
+fn parse_day_arg(val: &str) -> Result<HoursOverride, String> {
+    time::parse_hours_override(val).map_err(|e| e.to_string())
 }
 
 pub fn command(config: Cfg, args: PublishCommand) -> anyhow::Result<()> {
-    let (start_date, end_date) = time::get_date_range_from_user()?;
+    let (start_date, end_date) = args.range.resolve(config.user_data.initialize())?;
 
-    let vacation_days = time::get_day_range(
-        "Did you have any vacation days?",
-        "Pick vacation day",
-        start_date.date(),
-        end_date.date(),
-    )?;
-    let skip_days = time::get_day_range(
+    let imported = import_absences(config.user_data.inner(), &args, start_date.date(), end_date.date())?;
+
+    let absences = time::get_absences(start_date.date(), end_date.date(), imported.absences)?;
+    let mut skip_days = time::get_day_range(
         "Do you want to skip any days?",
         "Pick skip day",
         start_date.date(),
         end_date.date(),
     )?;
+    for holiday in imported.holidays {
+        if !skip_days.contains(&holiday) {
+            skip_days.push(holiday);
+        }
+    }
 
-    let mut step = Step::new(3);
+    let hours_overrides = if args.days.is_empty() {
+        time::get_hours_overrides(start_date.date(), end_date.date())?
+    } else {
+        args.days.clone()
+    };
+
+    if let Some(o) = hours_overrides
+        .iter()
+        .find(|o| o.date < start_date.date() || o.date > end_date.date())
+    {
+        bail!("--day {} is outside of the selected range", o.date);
+    }
+
+    let meetings = import_meetings(config.user_data.inner(), &args, start_date.date(), end_date.date())?;
+
+    let adjustments = DayAdjustments {
+        absences,
+        skip_days,
+        hours_overrides,
+        meetings,
+    };
+
+    let mut step = Step::new(4);
     println!("{} Resolving repositories...", step.get_str().bold());
     let user_data = config.user_data.initialize();
-    let repos = &config.repos.initialize().list;
+    if !config.repos.initialize().roots.is_empty() {
+        for path in repo::track_watched(config.repos.inner_mut()) {
+            println!(
+                "       Tracking {} found in a watched folder",
+                path.to_str().unwrap().bright_green()
+            );
+        }
+    }
+    let repos = &config.repos.inner().tracked(user_data.get_git_branch());
 
-    println!("{} Parsing commits...", step.get_str().bold());
-    let jira_payload = construct_jira_payload(
-        user_data.get_user_aliases(),
+    let sync = match (args.skip_fetch, args.pull) {
+        (true, _) => GitSync::Offline,
+        (_, true) => GitSync::Pull,
+        _ => GitSync::Fetch,
+    };
+    let failed = git_helpers::sync_all(
         repos,
-        start_date,
-        end_date,
-        vacation_days,
-        skip_days,
-        !args.skip_pull,
-    )?;
+        user_data.get_git_remote(),
+        sync,
+        &user_data.git_credentials(),
+        std::time::Duration::from_secs(args.fetch_timeout),
+    );
+    print_sync_failures(&failed);
+
+    println!("{} Parsing commits...", step.get_str().bold());
+    let tz = user_data.tz()?;
+    let cache_dir = commit_cache_dir();
+    let source = CommitSource {
+        aliases: user_data.get_user_aliases(),
+        author_email: &git_helpers::global_email()?,
+        remote: user_data.get_git_remote(),
+        comment_format: user_data.get_comment_format(),
+        commit_date: *user_data.get_commit_date(),
+        cache_dir: (!args.no_cache).then_some(cache_dir.as_path()),
+    };
+    let commits = collect_commits(repos, &source, start_date, end_date, tz)?;
+
+    let calendar = PlanCalendar {
+        first_day: start_date.date(),
+        last_day: end_date.date(),
+        adjustments: &adjustments,
+        absence_tickets: user_data.get_absence_tickets(),
+        fallback_ticket: user_data.fallback_ticket.as_deref(),
+        max_comment_length: user_data.get_comment_format().max_length,
+        tz,
+    };
+    let mut jira_payload = planning::plan(&commits, &calendar, user_data.get_work_schedule());
+
+    if commits.is_empty() {
+        println!("       {}", "No commits found in the selected range".yellow());
+    }
+    print_shortfalls(&planning::shortfalls(
+        &jira_payload,
+        &calendar,
+        user_data.get_work_schedule(),
+    ));
+
+    let expected = start_date
+        .date()
+        .iter_days()
+        .take_while(|d| *d <= end_date.date())
+        .map(|d| (d, adjustments.expected(user_data.get_work_schedule(), d)))
+        .filter(|(_, expected)| *expected > Duration::zero())
+        .collect::<Vec<_>>();
+
+    println!("{} Validating tickets...", step.get_str().bold());
+    let client = jira::JiraClient::new(config);
+    validate_tickets(&client, &mut jira_payload)?;
 
-    let sum = Duration::seconds(jira_payload.iter().map(|p| p.time_spent.num_seconds()).sum());
+    let keys_before = ticket_keys(&jira_payload);
+    let mut jira_payload = match editor::edit_plan(jira_payload, (start_date.date(), end_date.date()), &expected)? {
+        Some(val) => val,
+        None => bail!("Canceled"),
+    };
 
-    for jira_entry in &jira_payload {
+    if ticket_keys(&jira_payload) != keys_before {
+        println!("Validating changed tickets...");
+        validate_tickets(&client, &mut jira_payload)?;
+    }
+
+    let (to_publish, skipped): (Vec<_>, Vec<_>) = jira_payload
+        .into_iter()
+        .partition(|entry| !matches!(entry.ticket_id, JiraTicketId::Skipped));
+
+    // Saved before anything is sent and after every answer, so an interrupted run can still be undone
+    let mut report = PublishReport::new(start_date.date(), end_date.date());
+    for entry in to_publish.iter().chain(skipped.iter()) {
+        report.add(entry, None);
+    }
+    report.save_new().context("Nothing was published")?;
+    let report = Mutex::new(report);
+
+    println!("{} Publishing to jira...", step.get_str().bold());
+    let pb = pretty_print::get_progress_bar(to_publish.len() as u64);
+
+    client.publish_all(&to_publish, args.concurrency, |idx, entry, outcome| {
+        if !outcome.is_success() {
+            pb.println(format!(
+                "{} {} {} {}",
+                "Failed".bright_red(),
+                entry.ticket_id.to_str(),
+                outcome.status.map(|s| s.to_string()).unwrap_or_default(),
+                outcome.error.as_deref().unwrap_or("")
+            ));
+        }
+
+        let mut report = report.lock().unwrap();
+        report.record(idx, outcome);
+        if let Err(err) = report.save() {
+            pb.println(format!("{} {}", "Failed to save report".bright_red(), err));
+        }
+        pb.inc(1);
+    });
+    pb.finish_and_clear();
+
+    let mut report = report.into_inner().unwrap();
+    report.entries.sort_by_key(|e| e.started);
+
+    println!("{}", "Done".green());
+    print_report_summary(&report);
+
+    match report.save() {
+        Ok(path) => println!("Report saved to {}", path.to_string_lossy().bright_green()),
+        Err(err) => println!("{} {}", "Failed to save report".bright_red(), err),
+    }
+
+    Ok(())
+}
+
+fn import_absences(
+    user_data: &UserData,
+    args: &PublishCommand,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> anyhow::Result<ImportedAbsences> {
+    let path = match args.absences.as_ref().or(user_data.absences_path.as_ref()) {
+        Some(val) => val,
+        None => return Ok(ImportedAbsences::default()),
+    };
+
+    let imported = calendar::import_absences(path, from, to, user_data.tz()?)?;
+
+    println!("Imported from {}:", path.to_str().unwrap_or("").bright_green());
+    for absence in imported.absences.iter() {
         println!(
-            "       Date: {} Hours: {} Ticket: {} ({})",
-            jira_entry.started,
-            format!(
-                "{}.{}",
-                jira_entry.time_spent.num_hours(),
-                ((jira_entry.time_spent.num_minutes() - jira_entry.time_spent.num_hours() * 60) as f32 / 60_f32)
-                    .to_string()
-                    .split('.')
-                    .last()
-                    .unwrap()
-            )
-            .yellow(),
-            jira_entry.ticket_id.to_str().green(),
-            match jira_entry.ticket_id {
-                JiraTicketId::Pto => "PTO".red(),
-                JiraTicketId::DailyStandup => "Daily standup".yellow(),
-                JiraTicketId::Regular(_) => "Regular".bright_green(),
-                JiraTicketId::Skipped => "Skipped".red(),
-            }
+            "       {} {} ({})",
+            absence.date,
+            absence.kind.to_string().red(),
+            absence.portion
+        );
+    }
+    for holiday in imported.holidays.iter() {
+        println!("       {} {}", holiday, "Holiday".yellow());
+    }
+    if !imported.unrecognized.is_empty() {
+        println!(
+            "       Ignored events: {}",
+            imported.unrecognized.join(", ").bright_black()
+        );
+    }
+
+    Ok(imported)
+}
+
+fn import_meetings(
+    user_data: &UserData,
+    args: &PublishCommand,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> anyhow::Result<Vec<Meeting>> {
+    let path = match args.calendar.as_ref().or(user_data.calendar_path.as_ref()) {
+        Some(val) => val,
+        None => return Ok(vec![]),
+    };
+
+    let imported = calendar::import_meetings(path, from, to, user_data.tz()?, user_data.get_meeting_rules())?;
+
+    println!(
+        "Found {} meetings in {}",
+        imported.meetings.len().to_string().bright_green(),
+        path.to_str().unwrap_or("").bright_green()
+    );
+    if !imported.unmatched.is_empty() {
+        println!(
+            "       No ticket for: {} (add one with {})",
+            imported.unmatched.join(", ").bright_black(),
+            "tempo configure meetings add".green()
+        );
+    }
+    if !imported.unsupported.is_empty() {
+        println!(
+            "       Unsupported recurrence, skipped: {}",
+            imported.unsupported.join(", ").yellow()
         );
     }
+
+    Ok(imported.meetings)
+}
+
+/// Repos that couldn't be updated are still read, from whatever was fetched before
+fn print_sync_failures(failed: &[(PathBuf, String)]) {
+    if failed.is_empty() {
+        return;
+    }
+
     println!(
-        "                     Total hours to be logged: {}",
-        sum.num_hours().to_string().green()
+        "       {} {} repos failed to update, using their last fetched commits:",
+        "Warning:".yellow(),
+        failed.len()
     );
+    for (path, err) in failed {
+        println!("       {} {}", path.to_string_lossy().bright_red(), err);
+    }
+}
 
-    if !inquire::Confirm::new("Publish to jira?").prompt()? {
-        bail!("Canceled");
+/// Warns about days that won't be filled up to the schedule
+fn print_shortfalls(shortfalls: &[(NaiveDate, Duration, Duration)]) {
+    if shortfalls.is_empty() {
+        return;
     }
 
-    let pb = pretty_print::get_progress_bar(jira_payload.len() as u64);
-    println!("{} Publishing to jira...", step.get_str().bold());
+    println!(
+        "       {} {} days fall short of the schedule:",
+        "Warning:".yellow(),
+        shortfalls.len()
+    );
+    for (day, logged, expected) in shortfalls {
+        println!(
+            "       {} {} of {}",
+            day.format("%a %Y-%m-%d").to_string().bright_red(),
+            pretty_print::format_hours(*logged).yellow(),
+            pretty_print::format_hours(*expected).green(),
+        );
+    }
+    println!(
+        "       Set a ticket for time without commits with {}",
+        "tempo configure --fallback-ticket".green()
+    );
+}
+
+fn ticket_keys(payload: &[JiraTimeEntry]) -> Vec<String> {
+    let mut keys = payload
+        .iter()
+        .filter(|e| !matches!(e.ticket_id, JiraTicketId::Skipped))
+        .map(|e| e.ticket_id.to_str().to_string())
+        .collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+
+    keys
+}
+
+enum TicketProblem {
+    Malformed,
+    NotFound,
+    Closed(String),
+    NoWorklog,
+}
+
+impl std::fmt::Display for TicketProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "not a ticket key"),
+            Self::NotFound => write!(f, "does not exist"),
+            Self::Closed(status) => write!(f, "is closed ({})", status),
+            Self::NoWorklog => write!(f, "can't take worklogs"),
+        }
+    }
+}
 
-    let mut published_hours = Duration::zero();
+fn find_ticket_problems(client: &jira::JiraClient, keys: &[String]) -> anyhow::Result<Vec<(String, TicketProblem)>> {
+    let (valid, malformed): (Vec<_>, Vec<_>) = keys
+        .iter()
+        .cloned()
+        .partition(|k| jira::find_ticket_key(k).as_ref() == Some(k));
 
-    for jira_entry in jira_payload
+    let issues = client.find_issues(&valid)?;
+    let mut res = malformed
         .into_iter()
-        .filter(|entry| !matches!(entry.ticket_id, JiraTicketId::Skipped))
-    {
-        match jira::publish_entry(config, &jira_entry) {
-            Ok(res) => match res.status().as_u16() {
-                200..=299 => {
-                    published_hours = published_hours + jira_entry.time_spent;
-                }
-                _ => {
-                    println!("Failed {} {}", res.status(), res.text()?);
-                    println!("Published hours so far: {}", published_hours.num_hours());
-                    if !inquire::Confirm::new("You have a failed requiest, continue?").prompt()? {
-                        break;
-                    }
+        .map(|k| (k, TicketProblem::Malformed))
+        .collect::<Vec<_>>();
+
+    for key in valid {
+        match issues.iter().find(|i| i.key.eq_ignore_ascii_case(&key)) {
+            None => res.push((key, TicketProblem::NotFound)),
+            Some(issue) if !issue.can_log_work => res.push((key, TicketProblem::NoWorklog)),
+            Some(issue) if issue.closed => res.push((key, TicketProblem::Closed(issue.status.clone()))),
+            Some(_) => {}
+        }
+    }
+
+    Ok(res)
+}
+
+/// Checks every ticket of the payload exists and takes worklogs, lets the user remap or drop the ones that don't
+fn validate_tickets(client: &jira::JiraClient, payload: &mut Vec<JiraTimeEntry>) -> anyhow::Result<()> {
+    let keys = ticket_keys(payload);
+    let problems = find_ticket_problems(client, &keys)?;
+    if problems.is_empty() {
+        println!("       All {} tickets are valid", keys.len().to_string().green());
+        return Ok(());
+    }
+
+    for (key, problem) in problems {
+        let entries = payload.iter().filter(|e| e.ticket_id.to_str() == key).count();
+        println!("       {} {} ({} entries)", key.bright_red(), problem, entries);
+
+        let options = vec!["Remap to another ticket", "Drop its entries", "Keep"];
+        match inquire::Select::new(&format!("What to do with {}?", key), options)
+            .raw_prompt()?
+            .index
+        {
+            0 => {
+                let ticket = prompt_remap(client, &key)?;
+                for entry in payload.iter_mut().filter(|e| e.ticket_id.to_str() == key) {
+                    entry.ticket_id.set_ticket(ticket.clone());
                 }
-            },
-            Err(err) => {
-                println!("{} {:?}", "Request failed".bright_red(), err);
-                break;
             }
+            1 => payload.retain(|e| e.ticket_id.to_str() != key),
+            _ => {}
         }
+    }
 
-        pb.inc(1);
+    Ok(())
+}
+
+fn prompt_remap(client: &jira::JiraClient, key: &str) -> anyhow::Result<String> {
+    loop {
+        let ticket = inquire::Text::new(&format!("New ticket for {}", key)).prompt()?;
+        let ticket = ticket.trim().to_uppercase();
+
+        match find_ticket_problems(client, std::slice::from_ref(&ticket))?.pop() {
+            None => return Ok(ticket),
+            Some((_, problem)) => {
+                println!("       {} {}", ticket.bright_red(), problem);
+                if inquire::Confirm::new("Use it anyway?").with_default(false).prompt()? {
+                    return Ok(ticket);
+                }
+            }
+        }
     }
-    pb.finish_and_clear();
-    println!("{}", "Done".green());
+}
+
+pub fn print_report_summary(report: &PublishReport) {
     println!(
-        "Total hours published: {}",
-        published_hours.num_hours().to_string().green()
+        "Created: {} ({} hours)  Failed: {}  Skipped: {}",
+        report.count(EntryStatus::Created).to_string().green(),
+        pretty_print::format_hours(report.time_spent(EntryStatus::Created)).green(),
+        match report.count(EntryStatus::Failed) {
+            0 => "0".green(),
+            failed => failed.to_string().bright_red(),
+        },
+        report.count(EntryStatus::Skipped),
     );
 
-    Ok(())
+    let unknown = report.count(EntryStatus::Unknown);
+    if unknown > 0 {
+        println!(
+            "{} {} requests timed out, check jira before publishing them again (tempo history show {})",
+            "Warning:".yellow(),
+            unknown,
+            report.id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FixtureRepo, MockJira};
+    use chrono::TimeZone;
+    use chrono_tz::Europe::Zagreb;
+
+    #[test]
+    fn commits_from_a_repo_end_up_as_worklogs() {
+        let repo = FixtureRepo::new();
+        let fix = repo.commit(
+            "ART-1: Fix login",
+            "dev@example.com",
+            Zagreb.with_ymd_and_hms(2026, 9, 14, 10, 0, 0).unwrap(),
+        );
+        repo.commit(
+            "ART-2: Skipped",
+            "other@example.com",
+            Zagreb.with_ymd_and_hms(2026, 9, 14, 11, 0, 0).unwrap(),
+        );
+        repo.commit(
+            "ART-3: Too late",
+            "dev@example.com",
+            Zagreb.with_ymd_and_hms(2026, 9, 15, 10, 0, 0).unwrap(),
+        );
+
+        let day = NaiveDate::from_ymd_opt(2026, 9, 14).unwrap();
+        let (start, end) = time::to_datetime_range(day, day);
+        let cache = tempfile::tempdir().unwrap();
+        let source = CommitSource {
+            aliases: &[],
+            author_email: "dev@example.com",
+            remote: "origin",
+            comment_format: &CommentFormat::default(),
+            commit_date: CommitDate::Committed,
+            cache_dir: Some(cache.path()),
+        };
+        let commits = collect_commits(
+            &[(repo.path().to_path_buf(), String::from("develop"))],
+            &source,
+            start,
+            end,
+            Zagreb,
+        )
+        .unwrap();
+        let adjustments = DayAdjustments::default();
+        let tickets = std::collections::HashMap::new();
+        let calendar = PlanCalendar {
+            first_day: day,
+            last_day: day,
+            adjustments: &adjustments,
+            absence_tickets: &tickets,
+            fallback_ticket: None,
+            max_comment_length: 1000,
+            tz: Zagreb,
+        };
+        let payload = planning::plan(&commits, &calendar, &WorkSchedule::default());
+
+        let jira = MockJira::start();
+        for entry in payload.iter() {
+            jira.add_issue(entry.ticket_id.to_str());
+        }
+
+        let outcomes = jira.client().publish_all(&payload, 4, |_, _, _| {});
+        assert!(outcomes.iter().all(|o| o.is_success()));
+
+        let repo_name = repo.path().file_name().unwrap().to_str().unwrap().to_string();
+        let art1 = jira.worklogs().into_iter().find(|w| w.issue_key == "ART-1").unwrap();
+        assert_eq!(
+            art1.comment,
+            format!("(Auto generated) \n[{}@{}] Fix login", repo_name, &fix.to_string()[..8])
+        );
+
+        let mut worklogs = jira
+            .worklogs()
+            .into_iter()
+            .map(|w| (w.issue_key, w.started, w.time_spent_seconds))
+            .collect::<Vec<_>>();
+        worklogs.sort();
+
+        assert_eq!(
+            worklogs,
+            vec![
+                ("ART-1".to_string(), "2026-09-14T09:30:00.000+0200".to_string(), 27000),
+                ("ART-1777".to_string(), "2026-09-14T09:00:00.000+0200".to_string(), 1800),
+            ]
+        );
+    }
 }
@@ -0,0 +1,387 @@
+use crate::{commands::unwrap_or_continue, data::*, pretty_print, time};
+use chrono::{Duration, NaiveDate};
+use colored::Colorize;
+use std::fmt::Formatter;
+
+#[derive(Clone, Copy)]
+enum Action {
+    Publish,
+    Reassign,
+    ChangeHours,
+    Merge,
+    Split,
+    Move,
+    Drop,
+    Cancel,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Publish => "Publish to jira",
+            Self::Reassign => "Reassign entry to another ticket",
+            Self::ChangeHours => "Change hours",
+            Self::Merge => "Merge entries",
+            Self::Split => "Split entry",
+            Self::Move => "Move entry to another day",
+            Self::Drop => "Drop entries",
+            Self::Cancel => "Cancel",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+pub fn print_plan(entries: &[JiraTimeEntry]) {
+    for (idx, entry) in entries.iter().enumerate() {
+        println!("  {:>3}. {}", idx + 1, entry_label(entry));
+    }
+}
+
+fn entry_label(entry: &JiraTimeEntry) -> String {
+    format!(
+        "Date: {} Hours: {} Ticket: {} ({})",
+        entry.started.format("%a %Y-%m-%d %H:%M"),
+        pretty_print::format_hours(entry.time_spent).yellow(),
+        entry.ticket_id.to_str().green(),
+        match entry.ticket_id {
+            JiraTicketId::Absence(kind, _) => kind.to_string().red(),
+            JiraTicketId::DailyStandup(_) => "Daily standup".yellow(),
+            JiraTicketId::Meeting(_) => "Meeting".yellow(),
+            JiraTicketId::Regular(_) => "Regular".bright_green(),
+            JiraTicketId::Skipped => "Skipped".red(),
+        }
+    )
+}
+
+/// Prints days whose logged time differs from what is expected, and the totals
+fn print_totals(entries: &[JiraTimeEntry], expected: &[(NaiveDate, Duration)]) {
+    let logged_on = |day: NaiveDate| {
+        entries
+            .iter()
+            .filter(|e| e.started.date_naive() == day)
+            .fold(Duration::zero(), |acc, e| acc + e.time_spent)
+    };
+
+    let mut days = expected.to_vec();
+    for entry in entries.iter().filter(|e| !matches!(e.ticket_id, JiraTicketId::Skipped)) {
+        let day = entry.started.date_naive();
+        if !days.iter().any(|(d, _)| *d == day) {
+            days.push((day, Duration::zero()));
+        }
+    }
+    days.sort_by_key(|(d, _)| *d);
+
+    for (day, expected) in days {
+        let logged = logged_on(day);
+        if logged != expected {
+            println!(
+                "       {} {} logged, {} expected",
+                day.format("%a %Y-%m-%d").to_string().bright_red(),
+                pretty_print::format_hours(logged).yellow(),
+                pretty_print::format_hours(expected).green(),
+            );
+        }
+    }
+
+    let total = entries.iter().fold(Duration::zero(), |acc, e| acc + e.time_spent);
+    let total_expected = expected.iter().fold(Duration::zero(), |acc, (_, d)| acc + *d);
+    let total = pretty_print::format_hours(total);
+
+    println!(
+        "                     Total hours to be logged: {} / {}",
+        if total == pretty_print::format_hours(total_expected) {
+            total.green()
+        } else {
+            total.bright_red()
+        },
+        pretty_print::format_hours(total_expected).green()
+    );
+}
+
+fn pick_entry(entries: &[JiraTimeEntry], msg: &str) -> anyhow::Result<Option<usize>> {
+    let options = editable(entries);
+    if options.is_empty() {
+        return Ok(None);
+    }
+
+    let labels = options
+        .iter()
+        .map(|idx| entry_label(&entries[*idx]))
+        .collect::<Vec<_>>();
+    let picked = match inquire::Select::new(msg, labels).raw_prompt() {
+        Ok(val) => val,
+        Err(inquire::InquireError::OperationCanceled) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(Some(options[picked.index]))
+}
+
+fn pick_entries(entries: &[JiraTimeEntry], msg: &str) -> anyhow::Result<Vec<usize>> {
+    let options = editable(entries);
+    let labels = options
+        .iter()
+        .map(|idx| entry_label(&entries[*idx]))
+        .collect::<Vec<_>>();
+
+    let picked = inquire::MultiSelect::new(msg, labels).raw_prompt_skippable()?;
+
+    Ok(picked
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| options[p.index])
+        .collect())
+}
+
+/// Indices of entries that will actually be published
+fn editable(entries: &[JiraTimeEntry]) -> Vec<usize> {
+    (0..entries.len())
+        .filter(|idx| !matches!(entries[*idx].ticket_id, JiraTicketId::Skipped))
+        .collect()
+}
+
+fn prompt_hours(msg: &str) -> anyhow::Result<Duration> {
+    let val = inquire::Text::new(msg)
+        .with_validator(|val: &str| match time::parse_duration(val) {
+            Ok(hours) if hours <= Duration::zero() => Ok(inquire::validator::Validation::Invalid(
+                "Hours must be more than 0".into(),
+            )),
+            Ok(_) => Ok(inquire::validator::Validation::Valid),
+            Err(err) => Ok(inquire::validator::Validation::Invalid(err.to_string().into())),
+        })
+        .prompt()?;
+
+    time::parse_duration(&val)
+}
+
+fn change_hours(entries: &mut [JiraTimeEntry], idx: usize, hours: Duration) -> anyhow::Result<()> {
+    if hours <= Duration::zero() {
+        anyhow::bail!("Hours must be more than 0");
+    }
+    entries[idx].time_spent = hours;
+
+    Ok(())
+}
+
+/// Adds the time and comments of the other `picked` entries to the first one and removes them
+fn merge_entries(entries: &mut Vec<JiraTimeEntry>, picked: &[usize]) -> anyhow::Result<()> {
+    let (keep, rest) = match picked.split_first() {
+        Some((keep, rest)) if !rest.is_empty() => (*keep, rest),
+        _ => anyhow::bail!("Pick at least two entries"),
+    };
+
+    for idx in rest {
+        let (time_spent, comment) = (entries[*idx].time_spent, entries[*idx].comment.clone());
+        entries[keep].time_spent = entries[keep].time_spent + time_spent;
+        entries[keep].comment = format!("{}\n{}", entries[keep].comment, comment);
+    }
+    drop_entries(entries, rest);
+
+    Ok(())
+}
+
+/// Cuts `first` off the entry, the rest goes to `ticket` right after it
+fn split_entry(entries: &mut Vec<JiraTimeEntry>, idx: usize, first: Duration, ticket: String) -> anyhow::Result<()> {
+    if first <= Duration::zero() || first >= entries[idx].time_spent {
+        anyhow::bail!("The first part must be shorter than the entry");
+    }
+
+    let mut second = entries[idx].clone();
+    second.ticket_id.set_ticket(ticket);
+    second.time_spent = entries[idx].time_spent - first;
+    second.started = entries[idx].started + first;
+
+    entries[idx].time_spent = first;
+    entries.insert(idx + 1, second);
+
+    Ok(())
+}
+
+/// Moves the entry to the same time on `day`, which has to be in `range`
+fn move_entry(
+    entries: &mut [JiraTimeEntry],
+    idx: usize,
+    day: NaiveDate,
+    range: (NaiveDate, NaiveDate),
+) -> anyhow::Result<()> {
+    if day < range.0 || day > range.1 {
+        anyhow::bail!("{} is outside of {} - {}", day, range.0, range.1);
+    }
+
+    let started = entries[idx].started;
+    entries[idx].started = time::local_datetime(started.timezone(), day, started.time());
+    entries.sort_by_key(|e| e.started);
+
+    Ok(())
+}
+
+fn drop_entries(entries: &mut Vec<JiraTimeEntry>, picked: &[usize]) {
+    let mut idx = 0;
+    entries.retain(|_| {
+        let keep = !picked.contains(&idx);
+        idx += 1;
+        keep
+    });
+}
+
+/// Lets the user review and change the plan, returns None if publishing was canceled.
+/// Entries can only be moved to days within `range`
+pub fn edit_plan(
+    mut entries: Vec<JiraTimeEntry>,
+    range: (NaiveDate, NaiveDate),
+    expected: &[(NaiveDate, Duration)],
+) -> anyhow::Result<Option<Vec<JiraTimeEntry>>> {
+    let actions = vec![
+        Action::Publish,
+        Action::Reassign,
+        Action::ChangeHours,
+        Action::Merge,
+        Action::Split,
+        Action::Move,
+        Action::Drop,
+        Action::Cancel,
+    ];
+
+    loop {
+        println!();
+        print_plan(&entries);
+        print_totals(&entries, expected);
+
+        let edited = match inquire::Select::new("Plan", actions.clone()).prompt()? {
+            Action::Publish => return Ok(Some(entries)),
+            Action::Cancel => return Ok(None),
+            Action::Reassign => {
+                let idx = unwrap_or_continue!(pick_entry(&entries, "Reassign which entry?")?);
+                let ticket = inquire::Text::new("New ticket")
+                    .with_initial_value(entries[idx].ticket_id.to_str())
+                    .prompt()?;
+
+                entries[idx].ticket_id.set_ticket(ticket.trim().to_uppercase());
+                Ok(())
+            }
+            Action::ChangeHours => {
+                let idx = unwrap_or_continue!(pick_entry(&entries, "Change hours of which entry?")?);
+                change_hours(&mut entries, idx, prompt_hours("Hours (eg. 2h30m)")?)
+            }
+            Action::Merge => {
+                let picked = pick_entries(&entries, "Merge which entries? The first one is kept")?;
+                merge_entries(&mut entries, &picked)
+            }
+            Action::Split => {
+                let idx = unwrap_or_continue!(pick_entry(&entries, "Split which entry?")?);
+                let first = prompt_hours("Hours of the first part")?;
+                if first >= entries[idx].time_spent {
+                    println!("{}", "The first part must be shorter than the entry".red());
+                    continue;
+                }
+
+                let ticket = inquire::Text::new("Ticket of the second part")
+                    .with_initial_value(entries[idx].ticket_id.to_str())
+                    .prompt()?;
+                split_entry(&mut entries, idx, first, ticket.trim().to_uppercase())
+            }
+            Action::Move => {
+                let idx = unwrap_or_continue!(pick_entry(&entries, "Move which entry?")?);
+                let day = inquire::DateSelect::new("Move to")
+                    .with_default(entries[idx].started.date_naive().clamp(range.0, range.1))
+                    .with_min_date(range.0)
+                    .with_max_date(range.1)
+                    .prompt()?;
+
+                move_entry(&mut entries, idx, day, range)
+            }
+            Action::Drop => {
+                let picked = pick_entries(&entries, "Drop which entries?")?;
+                drop_entries(&mut entries, &picked);
+                Ok(())
+            }
+        };
+
+        if let Err(err) = edited {
+            println!("{}", err.to_string().red());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 9, d).unwrap()
+    }
+
+    fn entry(ticket: &str, d: u32, hours: i64) -> JiraTimeEntry {
+        JiraTimeEntry {
+            ticket_id: JiraTicketId::Regular(ticket.to_string()),
+            comment: format!("Work on {}", ticket),
+            started: chrono_tz::Europe::Zagreb.with_ymd_and_hms(2026, 9, d, 9, 0, 0).unwrap(),
+            time_spent: Duration::hours(hours),
+        }
+    }
+
+    fn tickets(entries: &[JiraTimeEntry]) -> Vec<String> {
+        entries.iter().map(|e| e.ticket_id.to_str().to_string()).collect()
+    }
+
+    #[test]
+    fn change_hours_rejects_empty_entries() {
+        let mut entries = vec![entry("ART-1", 14, 4)];
+
+        assert!(change_hours(&mut entries, 0, Duration::zero()).is_err());
+        assert_eq!(entries[0].time_spent, Duration::hours(4));
+
+        change_hours(&mut entries, 0, Duration::minutes(90)).unwrap();
+        assert_eq!(entries[0].time_spent, Duration::minutes(90));
+    }
+
+    #[test]
+    fn merge_keeps_the_first_picked_entry() {
+        let mut entries = vec![entry("ART-1", 14, 4), entry("ART-2", 14, 2), entry("ART-3", 14, 2)];
+
+        assert!(merge_entries(&mut entries, &[1]).is_err());
+        merge_entries(&mut entries, &[2, 0]).unwrap();
+
+        assert_eq!(tickets(&entries), ["ART-2", "ART-3"]);
+        assert_eq!(entries[1].time_spent, Duration::hours(6));
+        assert_eq!(entries[1].comment, "Work on ART-3\nWork on ART-1");
+    }
+
+    #[test]
+    fn split_puts_the_rest_after_the_first_part() {
+        let mut entries = vec![entry("ART-1", 14, 4), entry("ART-2", 15, 8)];
+
+        assert!(split_entry(&mut entries, 0, Duration::hours(4), String::from("ART-9")).is_err());
+        assert!(split_entry(&mut entries, 0, Duration::zero(), String::from("ART-9")).is_err());
+        split_entry(&mut entries, 0, Duration::hours(1), String::from("ART-9")).unwrap();
+
+        assert_eq!(tickets(&entries), ["ART-1", "ART-9", "ART-2"]);
+        assert_eq!(entries[0].time_spent, Duration::hours(1));
+        assert_eq!(entries[1].time_spent, Duration::hours(3));
+        assert_eq!(entries[1].started, entries[0].started + Duration::hours(1));
+    }
+
+    #[test]
+    fn move_stays_within_the_range() {
+        let mut entries = vec![entry("ART-1", 14, 4), entry("ART-2", 15, 4)];
+        let range = (day(14), day(18));
+
+        assert!(move_entry(&mut entries, 0, day(19), range).is_err());
+        move_entry(&mut entries, 0, day(16), range).unwrap();
+
+        assert_eq!(tickets(&entries), ["ART-2", "ART-1"]);
+        assert_eq!(entries[1].started.date_naive(), day(16));
+        assert_eq!(entries[1].started.time(), entries[0].started.time());
+    }
+
+    #[test]
+    fn drop_removes_the_picked_entries() {
+        let mut entries = vec![entry("ART-1", 14, 4), entry("ART-2", 14, 2), entry("ART-3", 15, 2)];
+
+        drop_entries(&mut entries, &[0, 2]);
+
+        assert_eq!(tickets(&entries), ["ART-2"]);
+    }
+}
@@ -1,7 +1,9 @@
+use crate::git_helpers::{self, FetchContext};
 use crate::pretty_print;
 
 use super::*;
-use std::path::PathBuf;
+use chrono::{Datelike, Months};
+use std::path::{Path, PathBuf};
 
 #[derive(Args, Debug)]
 #[command(about = "Manage tracked git repos")]
@@ -13,11 +15,40 @@ pub struct RepoCommand {
 #[derive(Subcommand, Debug)]
 pub enum RepoCommandAction {
     #[command(about = "Add a repository")]
-    Add { path: PathBuf },
+    Add {
+        #[arg(required_unless_present = "remote")]
+        path: Option<PathBuf>,
+        #[arg(
+            long,
+            conflicts_with = "path",
+            help = "Url of a repo you don't have cloned, tempo keeps a mirror of it"
+        )]
+        remote: Option<String>,
+        #[arg(
+            long,
+            requires = "remote",
+            help = "Branch to read from the mirror, the configured git branch by default"
+        )]
+        branch: Option<String>,
+    },
     #[command(about = "Delete a repository")]
     Rm,
     #[command(about = "List all repositories")]
     Ls,
+    #[command(about = "Find repositories under a folder and pick which to track")]
+    Scan {
+        dir: PathBuf,
+        #[arg(long, default_value_t = 3, help = "How many folders deep to look")]
+        depth: usize,
+        #[arg(long, default_value_t = 3, help = "Months to count your commits in")]
+        months: u32,
+        #[arg(long, help = "Track repos cloned under the folder later on when publishing")]
+        watch: bool,
+    },
+    #[command(about = "Stop picking up new repos under watched folders")]
+    Unwatch,
+    #[command(about = "Check that every tracked repo can be read from")]
+    Check,
 }
 #[derive(Args, Debug)]
 struct AddArgs {
@@ -28,7 +59,13 @@ pub fn command(config: Cfg, args: RepoCommandAction) -> anyhow::Result<()> {
     config.repos.initialize();
 
     match args {
-        RepoCommandAction::Add { path } => {
+        RepoCommandAction::Add {
+            remote: Some(url),
+            branch,
+            ..
+        } => add_mirror(config, &url, branch)?,
+        RepoCommandAction::Add { path: None, .. } => {}
+        RepoCommandAction::Add { path: Some(path), .. } => {
             let full_path = match std::fs::canonicalize(path) {
                 Ok(val) => val,
                 Err(_) => {
@@ -50,33 +87,353 @@ pub fn command(config: Cfg, args: RepoCommandAction) -> anyhow::Result<()> {
             for (idx, path) in config.repos.inner().list.iter().enumerate() {
                 pretty_print::print_row(idx, path.to_str().unwrap());
             }
+
+            if !config.repos.inner().mirrors.is_empty() {
+                println!("Mirrored repos: ");
+                for (idx, mirror) in config.repos.inner().mirrors.iter().enumerate() {
+                    match &mirror.branch {
+                        Some(branch) => pretty_print::print_row(idx, &format!("{} ({})", mirror.url, branch)),
+                        None => pretty_print::print_row(idx, &mirror.url),
+                    }
+                }
+            }
+
+            if !config.repos.inner().roots.is_empty() {
+                println!("Watched folders: ");
+                for (idx, root) in config.repos.inner().roots.iter().enumerate() {
+                    pretty_print::print_row(idx, &format!("{} (depth {})", root.path.display(), root.depth));
+                }
+            }
+        }
+        RepoCommandAction::Scan {
+            dir,
+            depth,
+            months,
+            watch,
+        } => scan(config, &dir, depth, months, watch)?,
+        RepoCommandAction::Check => {
+            let user_data = config.user_data.initialize();
+            let email = git_helpers::global_email()?;
+            let month_start = Local::now().date_naive().with_day(1).unwrap();
+            let since = month_start
+                .and_hms_opt(0, 0, 0)
+                .and_then(|d| d.and_local_timezone(Local).earliest())
+                .map_or(0, |d| d.timestamp());
+
+            let tracked = config.repos.inner().tracked(user_data.get_git_branch());
+            let mut broken = 0;
+            for (path, branch) in tracked.iter() {
+                let checks = check_repo(path, user_data.get_git_remote(), branch, since, |commit_email| {
+                    is_author(&email, user_data.get_user_aliases(), commit_email)
+                });
+
+                if checks.iter().any(|(ok, _)| !ok) {
+                    broken += 1;
+                }
+
+                println!("{}", path.to_str().unwrap().bold());
+                for (ok, line) in checks {
+                    let mark = if ok { "ok".green() } else { "!!".bright_red() };
+                    println!("  {} {}", mark, line);
+                }
+            }
+
+            if broken > 0 {
+                bail_ok!("{} of {} repos have problems", broken, tracked.len());
+            }
+            println!("{}", "All repos look fine".bright_green());
+        }
+        RepoCommandAction::Unwatch => {
+            let roots = &config.repos.inner().roots;
+
+            if roots.is_empty() {
+                bail_ok!("Not watching any folders...");
+            }
+
+            let options = roots.iter().map(|r| r.path.to_str().unwrap()).collect::<Vec<_>>();
+            let res = match inquire::MultiSelect::new("Stop watching", options).prompt() {
+                Ok(val) => val.into_iter().map(PathBuf::from).collect::<Vec<_>>(),
+                Err(_) => return Ok(()),
+            };
+
+            config.repos.inner_mut().roots.retain(|r| !res.contains(&r.path));
         }
         RepoCommandAction::Rm => {
-            let list = &mut config.repos.inner_mut().list;
+            let repos = config.repos.inner();
 
-            if list.is_empty() {
+            if repos.list.is_empty() && repos.mirrors.is_empty() {
                 bail_ok!("Not currently tracking any repos...");
             }
 
-            let options = list.iter().map(|path| path.to_str().unwrap()).collect::<Vec<_>>();
+            let options = repos
+                .list
+                .iter()
+                .map(|path| path.to_str().unwrap().to_string())
+                .chain(repos.mirrors.iter().map(|m| m.url.clone()))
+                .collect::<Vec<_>>();
 
             let res = match inquire::MultiSelect::new("Delete repo(s)", options).prompt() {
                 Ok(val) => val,
                 Err(_) => return Ok(()),
             };
 
-            if res.len() == list.len() {
-                list.clear();
+            let repos = config.repos.inner_mut();
+            repos
+                .list
+                .retain(|item| !res.contains(&item.to_str().unwrap().to_string()));
+            for mirror in repos.mirrors.iter().filter(|m| res.contains(&m.url)) {
+                let _ = std::fs::remove_dir_all(&mirror.path);
+            }
+            repos.mirrors.retain(|m| !res.contains(&m.url));
+
+            if repos.list.is_empty() && repos.mirrors.is_empty() {
                 bail_ok!("{}", "Removed all tracked repos".red());
             }
+        }
+    }
 
-            config.repos.inner_mut().list = list
-                .iter()
-                .filter(|item| !(*res).contains(&item.to_str().unwrap()))
-                .cloned()
-                .collect::<Vec<_>>();
+    Ok(())
+}
+
+fn add_mirror(config: Cfg, url: &str, branch: Option<String>) -> anyhow::Result<()> {
+    let path = mirror_dir(url);
+    // The https and ssh urls of a repo share a mirror, adding both would delete the first one's
+    if let Some(existing) = config
+        .repos
+        .inner()
+        .mirrors
+        .iter()
+        .find(|m| m.url == url || m.path == path)
+    {
+        bail_ok!("{} Already added as {}", url.green(), existing.url);
+    }
+
+    if path.exists() {
+        std::fs::remove_dir_all(&path)?;
+    }
+
+    let user_data = config.user_data.initialize();
+    let mut credentials = user_data.git_credentials();
+    if git_helpers::is_ssh_url(url) {
+        credentials.unlock_keys();
+    }
+    let ctx = FetchContext {
+        credentials: &credentials,
+        progress: pretty_print::get_fetch_bar(url),
+        deadline: std::time::Instant::now() + std::time::Duration::from_secs(10 * 60),
+    };
+
+    let fetched_branch = branch.as_deref().unwrap_or(user_data.get_git_branch());
+    let res = git_helpers::create_mirror(&path, url, user_data.get_git_remote(), fetched_branch, &ctx);
+    ctx.progress.finish_and_clear();
+    if let Err(err) = res {
+        let _ = std::fs::remove_dir_all(&path);
+        bail_ok!("{} {}", "Failed to mirror the repo:".bright_red(), err);
+    }
+
+    config.repos.inner_mut().mirrors.push(Mirror {
+        url: url.to_string(),
+        path,
+        branch,
+    });
+    bail_ok!("Added {}", url.bright_green());
+}
+
+fn scan(config: Cfg, dir: &Path, depth: usize, months: u32, watch: bool) -> anyhow::Result<()> {
+    let root = match std::fs::canonicalize(dir) {
+        Ok(val) => val,
+        Err(_) => {
+            println!("{}", "Invalid path".bright_red());
+            return Ok(());
         }
+    };
+
+    let found = git_helpers::find_repos(&root, depth);
+    if watch {
+        let watched = WatchedRoot {
+            path: root.clone(),
+            depth,
+            seen: found.clone(),
+        };
+        let roots = &mut config.repos.inner_mut().roots;
+        roots.retain(|r| r.path != root);
+        roots.push(watched);
+        println!(
+            "Watching {}, repos cloned there are tracked on publish",
+            root.to_str().unwrap().green()
+        );
+    }
+
+    let tracked = &config.repos.inner().list;
+    let untracked = found.iter().filter(|p| !tracked.contains(p)).collect::<Vec<_>>();
+    if untracked.is_empty() {
+        bail_ok!("No untracked repos in {}", root.to_str().unwrap().green());
+    }
+
+    let email = git_helpers::global_email()?;
+    let aliases = config.user_data.initialize().get_user_aliases().clone();
+    let since = Local::now()
+        .checked_sub_months(Months::new(months))
+        .map_or(0, |d| d.timestamp());
+
+    let counts = untracked
+        .iter()
+        .map(|path| -> anyhow::Result<usize> {
+            let repo = git2::Repository::open(path)?;
+            let head = repo.head()?.peel_to_commit()?.id();
+
+            git_helpers::count_commits_since(&repo, head, since, |commit_email| {
+                is_author(&email, &aliases, commit_email)
+            })
+        })
+        .map(|count| count.unwrap_or(0))
+        .collect::<Vec<_>>();
+
+    let options = untracked
+        .iter()
+        .zip(&counts)
+        .map(|(path, count)| format!("{} ({} commits in {} months)", path.to_str().unwrap(), count, months))
+        .collect::<Vec<_>>();
+    let with_commits = (0..counts.len()).filter(|idx| counts[*idx] > 0).collect::<Vec<_>>();
+
+    let res = match inquire::MultiSelect::new("Track repo(s)", options)
+        .with_default(&with_commits)
+        .raw_prompt()
+    {
+        Ok(val) => val,
+        Err(_) => return Ok(()),
+    };
+
+    let list = &mut config.repos.inner_mut().list;
+    for picked in res {
+        let path = untracked[picked.index];
+        list.push(path.clone());
+        println!("Added {}", path.to_str().unwrap().bright_green());
     }
 
     Ok(())
 }
+
+/// What's fine and what isn't about a tracked repo, stops at the first problem nothing else can be checked after
+fn check_repo(
+    path: &Path,
+    remote: &str,
+    branch: &str,
+    since: i64,
+    is_users: impl Fn(&str) -> bool,
+) -> Vec<(bool, String)> {
+    if !path.exists() {
+        return vec![(false, String::from("Path doesn't exist, was it moved?"))];
+    }
+
+    let repo = match git2::Repository::open(path) {
+        Ok(val) => val,
+        Err(err) => return vec![(false, format!("Doesn't open: {}", err.message()))],
+    };
+
+    let mut checks = vec![];
+    match repo.find_remote(remote) {
+        Ok(r) => checks.push((true, format!("Remote {} at {}", remote, r.url().unwrap_or("?")))),
+        Err(_) => checks.push((false, format!("No remote {}", remote))),
+    }
+
+    let remote_ref = format!("refs/remotes/{}/{}", remote, branch);
+    let local_ref = format!("refs/heads/{}", branch);
+    if repo.find_reference(&remote_ref).is_ok() {
+        checks.push((true, format!("Reading {}/{}", remote, branch)));
+    } else if repo.find_reference(&local_ref).is_ok() {
+        checks.push((
+            false,
+            format!("Reading the local {}, {}/{} was never fetched", branch, remote, branch),
+        ));
+    } else {
+        checks.push((false, format!("No branch {}", branch)));
+        return checks;
+    }
+
+    let last_fetch = std::fs::metadata(repo.path().join("FETCH_HEAD")).and_then(|m| m.modified());
+    match last_fetch {
+        Ok(time) => {
+            let time = chrono::DateTime::<Local>::from(time);
+            checks.push((true, format!("Last fetched {}", time.format("%Y-%m-%d %H:%M"))));
+        }
+        Err(_) => checks.push((false, String::from("Never fetched"))),
+    }
+
+    let count = git_helpers::branch_tip(&repo, remote, branch)
+        .and_then(|tip| git_helpers::count_commits_since(&repo, tip, since, is_users));
+    match count {
+        Ok(count) => checks.push((true, format!("{} commits by you this month", count))),
+        Err(err) => checks.push((false, format!("Couldn't read commits: {}", err))),
+    }
+
+    checks
+}
+
+/// Tracks repos cloned under watched folders since they were last looked at, returns them
+pub fn track_watched(repos: &mut Repos) -> Vec<PathBuf> {
+    let mut added = vec![];
+
+    for root in repos.roots.iter_mut() {
+        let found = git_helpers::find_repos(&root.path, root.depth);
+        for path in found.iter() {
+            if !root.seen.contains(path) && !repos.list.contains(path) {
+                repos.list.push(path.clone());
+                added.push(path.clone());
+            }
+        }
+        root.seen = found;
+    }
+
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FixtureRepo;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn new_clones_under_watched_folders_are_tracked_once() {
+        let root = tempfile::tempdir().unwrap();
+        let skipped = root.path().join("skipped");
+        git2::Repository::init(&skipped).unwrap();
+
+        let mut repos = Repos::default();
+        repos.roots.push(WatchedRoot {
+            path: root.path().to_path_buf(),
+            depth: 2,
+            seen: vec![skipped.clone()],
+        });
+
+        let cloned = root.path().join("team/cloned");
+        git2::Repository::init(&cloned).unwrap();
+
+        assert_eq!(track_watched(&mut repos), std::slice::from_ref(&cloned));
+        assert_eq!(repos.list, [cloned]);
+        assert!(track_watched(&mut repos).is_empty());
+    }
+
+    #[test]
+    fn checks_point_out_what_is_wrong() {
+        let at = |day| Utc.with_ymd_and_hms(2026, 9, day, 8, 0, 0).unwrap();
+        let origin = FixtureRepo::new();
+        origin.commit("ART-1: Old", "dev@example.com", at(1));
+        origin.commit("ART-2: Recent", "dev@example.com", at(10));
+        let clone = FixtureRepo::clone_of(&origin);
+        let is_users = |email: &str| email == "dev@example.com";
+
+        let checks = check_repo(clone.path(), "origin", "develop", at(5).timestamp(), is_users);
+        assert!(checks.iter().all(|(ok, _)| *ok), "{:?}", checks);
+        assert_eq!(checks.last().unwrap().1, "1 commits by you this month");
+
+        let checks = check_repo(origin.path(), "origin", "develop", at(5).timestamp(), is_users);
+        assert_eq!(checks[0], (false, String::from("No remote origin")));
+        assert!(!checks[1].0);
+
+        let checks = check_repo(&origin.path().join("gone"), "origin", "develop", 0, is_users);
+        assert_eq!(checks.len(), 1);
+        assert!(!checks[0].0);
+    }
+}
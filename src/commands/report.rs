@@ -0,0 +1,286 @@
+use crate::jira::{self, Worklog};
+use crate::pretty_print;
+
+use super::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Group for worklogs whose comment doesn't name a repo
+const NO_REPO: &str = "(no repo)";
+
+#[derive(Args, Debug)]
+#[command(about = "Show hours logged to jira in a range")]
+pub struct ReportCommand {
+    #[command(flatten)]
+    range: DateRangeArgs,
+
+    #[arg(long, value_enum, default_value = "ticket")]
+    group_by: GroupBy,
+
+    #[arg(long, value_enum, default_value = "table")]
+    format: ReportFormat,
+
+    #[arg(short, long, help = "File to write csv or json to instead of printing it")]
+    output: Option<PathBuf>,
+}
+
+#[derive(clap::ValueEnum, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Ticket,
+    Day,
+    /// Jira project, the part of the ticket before the dash
+    Project,
+    /// Repo named in the comment by the comment template, time is split between repos by their commits
+    Repo,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct ReportRow {
+    group: String,
+    hours: f64,
+    seconds: i64,
+    worklogs: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct HoursReport {
+    from: NaiveDate,
+    to: NaiveDate,
+    group_by: GroupBy,
+    total_hours: f64,
+    rows: Vec<ReportRow>,
+}
+
+pub fn command(config: Cfg, args: ReportCommand) -> anyhow::Result<()> {
+    if args.output.is_some() && args.format == ReportFormat::Table {
+        anyhow::bail!("--output needs --format csv or json, tables are only printed");
+    }
+    let (start_date, end_date) = args.range.resolve(config.user_data.initialize())?;
+    let (from, to) = (start_date.date(), end_date.date());
+
+    let client = jira::JiraClient::new(config);
+    let me = client.myself()?;
+    let worklogs = client.find_worklogs(&me, from, to)?;
+
+    let rows = group_worklogs(&worklogs, args.group_by, config.user_data.inner().get_comment_format());
+    let total_seconds = rows.iter().map(|r| r.seconds).sum::<i64>();
+    let report = HoursReport {
+        from,
+        to,
+        group_by: args.group_by,
+        total_hours: to_hours(total_seconds),
+        rows,
+    };
+
+    let mut out: Box<dyn Write> = match (&args.output, args.format) {
+        (_, ReportFormat::Table) => return print_table(&report, total_seconds),
+        (Some(path), _) => Box::new(std::fs::File::create(path)?),
+        (None, _) => Box::new(std::io::stdout()),
+    };
+
+    match args.format {
+        ReportFormat::Csv => write_csv(&report.rows, &mut out)?,
+        _ => serde_json::to_writer_pretty(&mut out, &report)?,
+    }
+    out.flush()?;
+
+    if let Some(path) = args.output {
+        println!("Saved to {}", path.to_string_lossy().bright_green());
+    }
+
+    Ok(())
+}
+
+fn print_table(report: &HoursReport, total_seconds: i64) -> anyhow::Result<()> {
+    if report.rows.is_empty() {
+        bail_ok!("Nothing logged between {} and {}", report.from, report.to);
+    }
+
+    let width = report.rows.iter().map(|r| r.group.len()).max().unwrap_or(0);
+    for row in report.rows.iter() {
+        println!(
+            "  {:<width$}  {:>7}h  {} worklogs",
+            row.group,
+            pretty_print::format_hours(Duration::seconds(row.seconds)),
+            row.worklogs,
+            width = width
+        );
+    }
+    println!(
+        "  {:<width$}  {:>7}h",
+        "Total".bold(),
+        pretty_print::format_hours(Duration::seconds(total_seconds)).bright_green(),
+        width = width
+    );
+
+    Ok(())
+}
+
+fn write_csv(rows: &[ReportRow], out: impl Write) -> anyhow::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn to_hours(seconds: i64) -> f64 {
+    (seconds as f64 / 36.0).round() / 100.0
+}
+
+/// Hours per group, sorted by the group name
+fn group_worklogs(worklogs: &[Worklog], by: GroupBy, format: &CommentFormat) -> Vec<ReportRow> {
+    let mut groups = BTreeMap::<String, (i64, usize)>::new();
+
+    for worklog in worklogs {
+        let seconds = worklog.time_spent.num_seconds();
+        let parts = match by {
+            GroupBy::Ticket => vec![(worklog.issue_key.clone(), seconds)],
+            GroupBy::Day => vec![(worklog.started.date_naive().to_string(), seconds)],
+            GroupBy::Project => {
+                let project = worklog.issue_key.split('-').next().unwrap_or("");
+                vec![(project.to_string(), seconds)]
+            }
+            GroupBy::Repo => split_by_repo(&worklog.comment, seconds, format),
+        };
+
+        for (group, seconds) in parts {
+            let entry = groups.entry(group).or_default();
+            entry.0 += seconds;
+            entry.1 += 1;
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, (seconds, worklogs))| ReportRow {
+            group,
+            hours: to_hours(seconds),
+            seconds,
+            worklogs,
+        })
+        .collect()
+}
+
+/// Splits the time of a worklog between the repos of its commit lines, read with the configured comment template
+fn split_by_repo(comment: &str, seconds: i64, format: &CommentFormat) -> Vec<(String, i64)> {
+    let mut commits = BTreeMap::<String, i64>::new();
+    for repo in comment.lines().filter_map(|line| format.parse_repo(line)) {
+        *commits.entry(repo).or_default() += 1;
+    }
+
+    let total = commits.values().sum::<i64>();
+    if total == 0 {
+        return vec![(NO_REPO.to_string(), seconds)];
+    }
+
+    let mut parts = commits
+        .into_iter()
+        .map(|(repo, count)| (repo, seconds * count / total))
+        .collect::<Vec<_>>();
+    let rest = seconds - parts.iter().map(|p| p.1).sum::<i64>();
+    parts[0].1 += rest;
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockJira, MOCK_USER};
+
+    fn worklogs(jira: &MockJira) -> Vec<Worklog> {
+        let client = jira.client();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 9, d).unwrap();
+
+        client
+            .find_worklogs(&client.myself().unwrap(), day(1), day(30))
+            .unwrap()
+    }
+
+    #[test]
+    fn groups_own_worklogs_in_range() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        jira.add_issue("ART-2");
+        jira.add_issue("OPS-7");
+        jira.add_worklog("ART-1", MOCK_USER, "2026-09-14T09:00:00.000+0200", 3600);
+        jira.add_worklog("ART-1", MOCK_USER, "2026-09-15T09:00:00.000+0200", 5400);
+        jira.add_worklog("ART-2", MOCK_USER, "2026-09-14T13:00:00.000+0200", 1800);
+        jira.add_worklog("OPS-7", MOCK_USER, "2026-09-15T09:00:00.000+0200", 7200);
+        jira.add_worklog("OPS-7", "someone", "2026-09-15T09:00:00.000+0200", 7200);
+        jira.add_worklog("OPS-7", MOCK_USER, "2026-10-01T09:00:00.000+0200", 7200);
+        let worklogs = worklogs(&jira);
+
+        let summary = |by| {
+            group_worklogs(&worklogs, by, &CommentFormat::default())
+                .into_iter()
+                .map(|r| (r.group, r.hours, r.worklogs))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            summary(GroupBy::Ticket),
+            [
+                (String::from("ART-1"), 2.5, 2),
+                (String::from("ART-2"), 0.5, 1),
+                (String::from("OPS-7"), 2.0, 1)
+            ]
+        );
+        assert_eq!(
+            summary(GroupBy::Day),
+            [
+                (String::from("2026-09-14"), 1.5, 2),
+                (String::from("2026-09-15"), 3.5, 2)
+            ]
+        );
+        assert_eq!(
+            summary(GroupBy::Project),
+            [(String::from("ART"), 3.0, 3), (String::from("OPS"), 2.0, 1)]
+        );
+    }
+
+    #[test]
+    fn csv_has_a_row_per_group() {
+        let rows = vec![ReportRow {
+            group: String::from("ART-1"),
+            hours: 2.5,
+            seconds: 9000,
+            worklogs: 2,
+        }];
+        let mut out = vec![];
+
+        write_csv(&rows, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "group,hours,seconds,worklogs\nART-1,2.5,9000,2\n"
+        );
+    }
+
+    #[test]
+    fn repo_time_follows_the_commits() {
+        let comment = "[api@0123abcd] Fix login\n[api@4567abcd] Add test\n[web@89abcdef] Button";
+
+        assert_eq!(
+            split_by_repo(comment, 3601, &CommentFormat::default()),
+            [(String::from("api"), 2401), (String::from("web"), 1200)]
+        );
+        assert_eq!(
+            split_by_repo("(Auto generated) General development", 60, &CommentFormat::default()),
+            [(String::from(NO_REPO), 60)]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{jira, pretty_print};
+
+use super::*;
+
+#[derive(Args, Debug)]
+#[command(about = "Delete the worklogs created by a publish run, the latest one by default")]
+pub struct UndoCommand {
+    #[arg(help = "Run id as listed by `tempo history`")]
+    id: Option<String>,
+
+    #[arg(short = 'y', long, help = "Don't ask for confirmation")]
+    yes: bool,
+}
+
+pub fn command(config: Cfg, args: UndoCommand) -> anyhow::Result<()> {
+    let mut report = match args.id {
+        Some(id) => PublishReport::load(&id)?,
+        None => match PublishReport::list()?
+            .into_iter()
+            .rev()
+            .find(|r| r.undoable().next().is_some())
+        {
+            Some(val) => val,
+            None => {
+                bail_ok!("Nothing to undo");
+            }
+        },
+    };
+
+    let entries = report.undoable().cloned().collect::<Vec<_>>();
+    if entries.is_empty() {
+        bail_ok!("Run {} has no worklogs left to delete", report.id);
+    }
+
+    println!(
+        "Run {} ({} - {}), published at {}",
+        report.id.bright_green(),
+        report.from,
+        report.to,
+        report.created_at.format("%Y-%m-%d %H:%M")
+    );
+    for entry in entries.iter() {
+        println!(
+            "  {} {:>5}h {}",
+            entry.started.format("%a %Y-%m-%d %H:%M"),
+            pretty_print::format_hours(entry.time_spent()).yellow(),
+            entry.ticket.green(),
+        );
+    }
+
+    let total = entries.iter().fold(Duration::zero(), |acc, e| acc + e.time_spent());
+    println!(
+        "Total hours to be deleted: {}",
+        pretty_print::format_hours(total).bright_red()
+    );
+
+    let missing_ids = report
+        .entries
+        .iter()
+        .filter(|e| e.status == EntryStatus::Created && e.worklog_id.is_none())
+        .count();
+    if missing_ids > 0 {
+        println!(
+            "{} {} created entries have no worklog id and can't be undone",
+            "Warning:".yellow(),
+            missing_ids
+        );
+    }
+
+    if !args.yes
+        && !inquire::Confirm::new(&format!("Delete {} worklogs?", entries.len()))
+            .with_default(false)
+            .prompt()?
+    {
+        bail_ok!("Canceled");
+    }
+
+    config.user_data.initialize();
+    let client = jira::JiraClient::new(config);
+    let pb = pretty_print::get_progress_bar(entries.len() as u64);
+    let mut failed = 0;
+
+    for entry in report.entries.iter_mut() {
+        let worklog_id = match (&entry.status, entry.worklog_id.as_deref()) {
+            (EntryStatus::Created, Some(id)) => id,
+            _ => continue,
+        };
+
+        // A transport error is kept on the entry like a failed response, the report is saved either way
+        let failure = match client.delete_worklog(&entry.ticket, worklog_id) {
+            // Already deleted by hand in jira, nothing left to undo
+            Ok(response) if response.status().is_success() || response.status() == reqwest::StatusCode::NOT_FOUND => {
+                entry.status = EntryStatus::Deleted;
+                entry.error = None;
+                None
+            }
+            Ok(response) => Some(format!(
+                "{} {}",
+                response.status().as_u16(),
+                response.text().unwrap_or_default()
+            )),
+            Err(err) => Some(format!("{err:#}")),
+        };
+
+        if let Some(err) = failure {
+            failed += 1;
+            pb.println(format!("{} {} {}", "Failed".bright_red(), entry.ticket, err));
+            entry.error = Some(err);
+        }
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    report.save()?;
+
+    println!(
+        "Deleted: {}  Failed: {}",
+        (entries.len() - failed).to_string().green(),
+        match failed {
+            0 => "0".green(),
+            failed => failed.to_string().bright_red(),
+        }
+    );
+
+    Ok(())
+}
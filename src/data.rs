@@ -1,10 +1,23 @@
+mod absence;
+mod comment;
+mod commit_cache;
 mod config;
 mod jira_payload;
+mod meeting;
+mod report;
 mod repos;
+mod schedule;
 mod user_data;
 mod wrapper;
 
+pub use absence::*;
+pub use comment::*;
+pub use commit_cache::*;
 pub use config::*;
 pub use jira_payload::*;
+pub use meeting::*;
+pub use report::*;
+pub use repos::*;
+pub use schedule::*;
 pub use user_data::*;
 pub use wrapper::*;
@@ -0,0 +1,107 @@
+use super::{Meeting, WorkSchedule};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt::Formatter;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum AbsenceKind {
+    Pto,
+    Sick,
+    Other,
+}
+
+impl AbsenceKind {
+    pub fn all() -> Vec<AbsenceKind> {
+        vec![Self::Pto, Self::Sick, Self::Other]
+    }
+}
+
+impl std::fmt::Display for AbsenceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Pto => "PTO",
+            Self::Sick => "Sick leave",
+            Self::Other => "Other absence",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayPortion {
+    Full,
+    Half,
+    Custom(Duration),
+}
+
+impl DayPortion {
+    /// Absent time for a day with the given scheduled capacity
+    pub fn resolve(&self, capacity: Duration) -> Duration {
+        match self {
+            Self::Full => capacity,
+            Self::Half => capacity / 2,
+            Self::Custom(val) => *val.min(&capacity),
+        }
+    }
+}
+
+impl std::fmt::Display for DayPortion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "Full day"),
+            Self::Half => write!(f, "Half day"),
+            Self::Custom(val) => write!(f, "{}h{}m", val.num_hours(), val.num_minutes() % 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Absence {
+    pub date: NaiveDate,
+    pub kind: AbsenceKind,
+    pub portion: DayPortion,
+}
+
+/// Hours actually worked on a day, replacing whatever the schedule says
+#[derive(Debug, Clone, Copy)]
+pub struct HoursOverride {
+    pub date: NaiveDate,
+    pub time: Duration,
+}
+
+/// Calendar and user provided changes to the scheduled days of a range
+#[derive(Debug, Clone, Default)]
+pub struct DayAdjustments {
+    pub absences: Vec<Absence>,
+    pub skip_days: Vec<NaiveDate>,
+    pub hours_overrides: Vec<HoursOverride>,
+    pub meetings: Vec<Meeting>,
+}
+
+impl DayAdjustments {
+    /// Hours override for the day if there is one, otherwise the scheduled capacity
+    pub fn capacity(&self, schedule: &WorkSchedule, day: NaiveDate) -> Duration {
+        match self.hours_overrides.iter().rev().find(|o| o.date == day) {
+            Some(o) => o.time,
+            None => schedule.capacity(day),
+        }
+    }
+
+    /// Time that should end up logged on the day, absences included
+    pub fn expected(&self, schedule: &WorkSchedule, day: NaiveDate) -> Duration {
+        if self.skip_days.contains(&day) {
+            return Duration::zero();
+        }
+
+        self.capacity(schedule, day)
+    }
+
+    pub fn absences_on(&self, day: NaiveDate) -> impl Iterator<Item = &Absence> {
+        self.absences.iter().filter(move |a| a.date == day)
+    }
+
+    pub fn meetings_on(&self, day: NaiveDate) -> impl Iterator<Item = &Meeting> {
+        self.meetings.iter().filter(move |m| m.started.date_naive() == day)
+    }
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+pub const PLACEHOLDERS: [&str; 5] = ["repo", "sha", "subject", "branch", "url"];
+
+/// How every commit is written into the worklog comment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentFormat {
+    pub template: String,
+    pub max_length: usize,
+}
+
+impl Default for CommentFormat {
+    fn default() -> Self {
+        Self {
+            template: String::from("[{repo}@{sha}] {subject}"),
+            max_length: 1000,
+        }
+    }
+}
+
+/// What a template placeholder can be replaced with
+#[derive(Debug, Clone, Default)]
+pub struct CommitInfo {
+    pub repo: String,
+    pub sha: String,
+    pub subject: String,
+    pub branch: String,
+    pub url: Option<String>,
+}
+
+impl CommentFormat {
+    pub fn render(&self, commit: &CommitInfo) -> String {
+        let short_sha = &commit.sha[..commit.sha.len().min(8)];
+
+        self.template
+            .replace("{repo}", &commit.repo)
+            .replace("{sha}", short_sha)
+            .replace("{subject}", &commit.subject)
+            .replace("{branch}", &commit.branch)
+            .replace("{url}", commit.url.as_deref().unwrap_or(""))
+            .trim()
+            .to_string()
+    }
+
+    /// The `{repo}` of a comment line rendered with this template, None when the line doesn't fit it.
+    /// Each placeholder runs up to the text that follows it in the template, so `{repo}` needs some.
+    pub fn parse_repo(&self, line: &str) -> Option<String> {
+        let mut parts = self.template.trim().split('{');
+        let mut rest = line.trim().strip_prefix(parts.next()?)?;
+        let mut repo = None;
+
+        while let Some(part) = parts.next() {
+            let (name, literal) = part.split_once('}')?;
+            let value = if literal.is_empty() && parts.clone().next().is_none() {
+                std::mem::take(&mut rest)
+            } else if literal.is_empty() {
+                ""
+            } else {
+                let (value, after) = rest.split_once(literal)?;
+                rest = after;
+                value
+            };
+
+            if name == "repo" {
+                repo = Some(value.to_string()).filter(|r| !r.is_empty() && !r.contains(char::is_whitespace));
+            }
+        }
+
+        repo
+    }
+}
+
+/// Errors on `{..}` placeholders that can't be filled in
+pub fn validate_template(template: &str) -> anyhow::Result<()> {
+    for part in template.split('{').skip(1) {
+        let name = match part.split_once('}') {
+            Some((name, _)) => name,
+            None => anyhow::bail!("Unclosed placeholder in {}", template),
+        };
+
+        if !PLACEHOLDERS.contains(&name) {
+            anyhow::bail!(
+                "Unknown placeholder {{{}}}, available are {}",
+                name,
+                PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join(" ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Cuts `comment` to at most `max_length` characters, marking the cut with "..."
+pub fn truncate_comment(comment: &str, max_length: usize) -> String {
+    if comment.chars().count() <= max_length {
+        return comment.to_string();
+    }
+
+    let kept = comment.chars().take(max_length.saturating_sub(3)).collect::<String>();
+    format!("{}...", kept.trim_end())
+}
+
+/// Web link to a commit for GitHub, GitLab and Bitbucket remotes, both ssh and https
+pub fn commit_url(remote_url: &str, sha: &str) -> Option<String> {
+    let remote = remote_url.trim().trim_end_matches('/').trim_end_matches(".git");
+
+    let (host, path) = if let Some(rest) = remote.strip_prefix("https://").or(remote.strip_prefix("http://")) {
+        rest.split_once('/')?
+    } else if let Some(rest) = remote.strip_prefix("ssh://") {
+        let (host, path) = rest.split_once('/')?;
+        (host, path)
+    } else {
+        // scp-like syntax, eg. git@github.com:org/repo
+        remote.split_once(':')?
+    };
+
+    // Drop the user and the port
+    let host = host.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    if host.contains("github") {
+        Some(format!("https://{}/{}/commit/{}", host, path, sha))
+    } else if host.contains("gitlab") {
+        Some(format!("https://{}/{}/-/commit/{}", host, path, sha))
+    } else if host.contains("bitbucket") {
+        Some(format!("https://{}/{}/commits/{}", host, path, sha))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn commit_urls_from_remotes() {
+        assert_eq!(
+            commit_url("git@github.com:org/repo.git", SHA),
+            Some(format!("https://github.com/org/repo/commit/{}", SHA))
+        );
+        assert_eq!(
+            commit_url("https://gitlab.example.com/group/sub/repo.git", SHA),
+            Some(format!("https://gitlab.example.com/group/sub/repo/-/commit/{}", SHA))
+        );
+        assert_eq!(
+            commit_url("ssh://git@gitlab.com:2222/group/repo.git", SHA),
+            Some(format!("https://gitlab.com/group/repo/-/commit/{}", SHA))
+        );
+        assert_eq!(
+            commit_url("https://user@bitbucket.org/team/repo", SHA),
+            Some(format!("https://bitbucket.org/team/repo/commits/{}", SHA))
+        );
+        assert_eq!(commit_url("git@git.example.com:org/repo.git", SHA), None);
+        assert_eq!(commit_url("/srv/git/repo.git", SHA), None);
+    }
+
+    #[test]
+    fn reads_the_repo_back_from_the_template() {
+        let format = |template: &str| CommentFormat {
+            template: String::from(template),
+            max_length: 100,
+        };
+
+        let default = CommentFormat::default();
+        assert_eq!(default.parse_repo("[api@0123abcd] Fix login").as_deref(), Some("api"));
+        assert_eq!(default.parse_repo("(Auto generated) General development"), None);
+
+        let custom = format("{subject} ({repo}, {sha})");
+        assert_eq!(custom.parse_repo("Fix login (web, 0123abcd)").as_deref(), Some("web"));
+        assert_eq!(custom.parse_repo("[api@0123abcd] Fix login"), None);
+
+        assert_eq!(
+            format("{sha} {repo}").parse_repo("0123abcd infra").as_deref(),
+            Some("infra")
+        );
+        assert_eq!(format("{subject}").parse_repo("Fix login"), None);
+    }
+
+    #[test]
+    fn renders_all_placeholders() {
+        let format = CommentFormat {
+            template: String::from("{repo} {branch} {sha} {subject} {url}"),
+            max_length: 100,
+        };
+        let commit = CommitInfo {
+            repo: String::from("tempo"),
+            sha: String::from(SHA),
+            subject: String::from("Fix login"),
+            branch: String::from("develop"),
+            url: None,
+        };
+
+        assert_eq!(format.render(&commit), "tempo develop 01234567 Fix login");
+    }
+
+    #[test]
+    fn unknown_placeholders_are_rejected() {
+        assert!(validate_template("{repo}@{sha}: {subject} {url}").is_ok());
+        assert!(validate_template("{author} {subject}").is_err());
+        assert!(validate_template("{subject").is_err());
+    }
+
+    #[test]
+    fn truncates_on_characters() {
+        assert_eq!(truncate_comment("short", 10), "short");
+        assert_eq!(truncate_comment("žžžžžžžžžž", 6), "žžž...");
+        assert_eq!(truncate_comment("a b c d e f", 8), "a b c...");
+    }
+}
@@ -0,0 +1,293 @@
+use super::get_app_dir;
+use anyhow::Context;
+use git2::{Commit, Oid, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Older commits walked past the start of the range, in case their parents are newer because of clock skew
+const SLOP: usize = 5;
+
+/// Which date of a commit decides the day it's logged on
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum CommitDate {
+    /// When it was committed, rebases and cherry-picks move it to the day they happened
+    #[default]
+    Committed,
+    /// When it was first written
+    Authored,
+}
+
+/// What's needed from a commit to turn it into a worklog line
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CachedCommit {
+    pub oid: String,
+    pub email: String,
+    /// Unix seconds
+    pub commit_time: i64,
+    pub author_time: i64,
+    pub ticket: String,
+    pub subject: String,
+}
+
+impl CachedCommit {
+    /// Unix seconds
+    pub fn time(&self, date: CommitDate) -> i64 {
+        match date {
+            CommitDate::Committed => self.commit_time,
+            CommitDate::Authored => self.author_time,
+        }
+    }
+
+    /// None for commits without a `TICKET: subject` message
+    fn parse(commit: &Commit) -> Option<Self> {
+        let (ticket, rest) = commit.message()?.split_once(':')?;
+
+        Some(Self {
+            oid: commit.id().to_string(),
+            email: commit.author().email().unwrap_or("").to_string(),
+            commit_time: commit.time().seconds(),
+            author_time: commit.author().when().seconds(),
+            ticket: ticket.trim().to_string(),
+            subject: rest.trim().lines().next().unwrap_or("").to_string(),
+        })
+    }
+}
+
+/// Ticket commits of one branch of a repo, newest first, saved under ~/.tempo/cache.
+/// Everything reachable from `tip` newer than `horizon` is indexed, so most runs only walk the new commits.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommitCache {
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    #[serde(skip)]
+    dirty: bool,
+    tip: Option<String>,
+    horizon: i64,
+    commits: Vec<CachedCommit>,
+}
+
+impl Default for CommitCache {
+    fn default() -> Self {
+        Self {
+            path: None,
+            dirty: false,
+            tip: None,
+            horizon: i64::MAX,
+            commits: vec![],
+        }
+    }
+}
+
+pub fn commit_cache_dir() -> PathBuf {
+    get_app_dir().join("cache")
+}
+
+impl CommitCache {
+    /// Cache of `remote/branch` of the repo in `dir`, empty when there is none or it can't be read
+    pub fn open(dir: &Path, repo: &Repository, remote: &str, branch: &str) -> Self {
+        let name = format!("{}-{}-{}", repo.path().to_string_lossy(), remote, branch)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let path = dir.join(format!("{}.bin", name.trim_matches('_')));
+
+        let mut cache = std::fs::read(&path)
+            .ok()
+            .and_then(|content| bincode::deserialize::<Self>(&content).ok())
+            .unwrap_or_default();
+        cache.path = Some(path);
+
+        cache
+    }
+
+    pub fn commits(&self) -> &[CachedCommit] {
+        &self.commits
+    }
+
+    /// Indexes what's new since the last run, walks everything again when the branch was rewritten
+    /// or commits older than the indexed ones are needed
+    pub fn update(&mut self, repo: &Repository, tip: Oid, since: i64) -> anyhow::Result<()> {
+        let old_tip = self.tip.as_deref().and_then(|t| Oid::from_str(t).ok());
+
+        match old_tip {
+            Some(old) if old == tip && since >= self.horizon => return Ok(()),
+            Some(old) if since >= self.horizon && repo.graph_descendant_of(tip, old).unwrap_or(false) => {
+                let mut commits = walk_new(repo, tip, old)?;
+                commits.append(&mut self.commits);
+                self.commits = commits;
+                self.tip = Some(tip.to_string());
+                self.dirty = true;
+
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let mut commits = vec![];
+        walk_since(repo, tip, since, |commit| commits.extend(CachedCommit::parse(commit)))?;
+        self.commits = commits;
+        self.horizon = since;
+        self.tip = Some(tip.to_string());
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = match (&self.path, self.dirty) {
+            (Some(path), true) => path,
+            _ => return Ok(()),
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, bincode::serialize(self)?).with_context(|| format!("Failed to save {}", path.display()))
+    }
+}
+
+/// Ticket commits reachable from `tip` but not from `old_tip`, newest first
+fn walk_new(repo: &Repository, tip: Oid, old_tip: Oid) -> anyhow::Result<Vec<CachedCommit>> {
+    let mut rev = repo.revwalk()?;
+    rev.push(tip)?;
+    rev.hide(old_tip)?;
+    rev.set_sorting(Sort::TIME)?;
+
+    let mut commits = vec![];
+    for oid in rev {
+        commits.extend(CachedCommit::parse(&repo.find_commit(oid?)?));
+    }
+
+    Ok(commits)
+}
+
+/// Visits the commits from `tip` committed or authored since `since`, newest first by commit time.
+/// Stops once every pending parent was committed before `since` and `SLOP` older commits in a row
+/// didn't lead back into the range
+pub fn walk_since(repo: &Repository, tip: Oid, since: i64, mut visit: impl FnMut(&Commit)) -> anyhow::Result<()> {
+    let mut pending = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    pending.push((repo.find_commit(tip)?.time().seconds(), tip));
+    seen.insert(tip);
+
+    let mut slop = SLOP;
+    while let Some((time, oid)) = pending.pop() {
+        let commit = repo.find_commit(oid)?;
+
+        if time < since {
+            if slop == 0 {
+                return Ok(());
+            }
+            slop -= 1;
+        } else {
+            slop = SLOP;
+        }
+
+        for parent in commit.parents() {
+            if seen.insert(parent.id()) {
+                pending.push((parent.time().seconds(), parent.id()));
+            }
+        }
+
+        // Rebased commits can be committed before the range and still be authored in it
+        if time >= since || commit.author().when().seconds() >= since {
+            visit(&commit);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FixtureRepo;
+    use chrono::{TimeZone, Utc};
+
+    fn at(day: u32) -> chrono::DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 9, day, 8, 0, 0).unwrap()
+    }
+
+    fn tickets(cache: &CommitCache) -> Vec<&str> {
+        cache.commits().iter().map(|c| c.ticket.as_str()).collect()
+    }
+
+    #[test]
+    fn only_new_commits_are_walked_after_the_first_run() {
+        let repo = FixtureRepo::new();
+        let dir = tempfile::tempdir().unwrap();
+        repo.commit("ART-1: Old", "dev@example.com", at(1));
+        repo.commit("No ticket", "dev@example.com", at(9));
+        let tip = repo.commit("ART-2: First", "dev@example.com", at(10));
+
+        let mut cache = CommitCache::open(dir.path(), &repo.repo, "origin", "develop");
+        cache.update(&repo.repo, tip, at(5).timestamp()).unwrap();
+        cache.save().unwrap();
+        assert_eq!(tickets(&cache), ["ART-2"]);
+
+        let tip = repo.commit("ART-3: Second", "other@example.com", at(11));
+        let mut cache = CommitCache::open(dir.path(), &repo.repo, "origin", "develop");
+        assert_eq!(tickets(&cache), ["ART-2"]);
+
+        cache.update(&repo.repo, tip, at(6).timestamp()).unwrap();
+        assert_eq!(tickets(&cache), ["ART-3", "ART-2"]);
+        assert_eq!(cache.commits()[0].email, "other@example.com");
+        assert_eq!(cache.commits()[0].subject, "Second");
+    }
+
+    #[test]
+    fn older_ranges_and_rewritten_branches_walk_again() {
+        let repo = FixtureRepo::new();
+        let dir = tempfile::tempdir().unwrap();
+        repo.commit("ART-1: Old", "dev@example.com", at(1));
+        let tip = repo.commit("ART-2: New", "dev@example.com", at(10));
+
+        let mut cache = CommitCache::open(dir.path(), &repo.repo, "origin", "develop");
+        cache.update(&repo.repo, tip, at(5).timestamp()).unwrap();
+        assert_eq!(tickets(&cache), ["ART-2"]);
+
+        cache.update(&repo.repo, tip, at(1).timestamp()).unwrap();
+        assert_eq!(tickets(&cache), ["ART-2", "ART-1"]);
+
+        let other = FixtureRepo::new();
+        let rewritten = other.commit("ART-9: Unrelated", "dev@example.com", at(12));
+        let mut remote = repo.repo.remote_anonymous(other.path().to_str().unwrap()).unwrap();
+        remote
+            .fetch(&["+refs/heads/develop:refs/heads/rewritten"], None, None)
+            .unwrap();
+
+        cache.update(&repo.repo, rewritten, at(1).timestamp()).unwrap();
+        assert_eq!(tickets(&cache), ["ART-9"]);
+    }
+
+    #[test]
+    fn skewed_commit_times_dont_end_the_walk() {
+        let repo = FixtureRepo::new();
+        repo.commit("ART-0: Before", "dev@example.com", at(1));
+        repo.commit("ART-1: In range", "dev@example.com", at(8));
+        repo.commit("ART-2: Wrong clock", "dev@example.com", at(3));
+        let tip = repo.commit("ART-3: Latest", "dev@example.com", at(10));
+
+        let mut cache = CommitCache::default();
+        cache.update(&repo.repo, tip, at(5).timestamp()).unwrap();
+
+        assert_eq!(tickets(&cache), ["ART-3", "ART-1"]);
+    }
+
+    #[test]
+    fn rebased_commits_keep_their_author_date() {
+        let repo = FixtureRepo::new();
+        repo.commit("ART-0: Before", "dev@example.com", at(1));
+        repo.commit_authored("ART-1: Rebased", "dev@example.com", at(6), at(4));
+        let tip = repo.commit_authored("ART-2: Cherry-picked", "dev@example.com", at(7), at(12));
+
+        let mut cache = CommitCache::default();
+        cache.update(&repo.repo, tip, at(5).timestamp()).unwrap();
+
+        assert_eq!(tickets(&cache), ["ART-2", "ART-1"]);
+        let picked = &cache.commits()[0];
+        assert_eq!(picked.time(CommitDate::Committed), at(12).timestamp());
+        assert_eq!(picked.time(CommitDate::Authored), at(7).timestamp());
+    }
+}
@@ -1,160 +1,172 @@
-use crate::{commands::unwrap_or_continue, time};
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
-use git2::{BranchType, Commit, Repository, Sort};
-use std::{ops::Add, path::PathBuf};
-
-const DAILY_STANDUP_ID: &str = "ART-1777";
+use super::{commit_url, AbsenceKind, CommentFormat, CommitCache, CommitDate, CommitInfo};
+use crate::git_helpers;
+use crate::{commands::unwrap_or_continue, planning::TicketCommits};
+use chrono::{DateTime, Duration, TimeZone};
+use chrono_tz::Tz;
+use colored::Colorize;
+use git2::Repository;
+use std::path::{Path, PathBuf};
+
+pub const DAILY_STANDUP_ID: &str = "ART-1777";
 #[allow(unused)]
 const SPRINT_PLANNING_ID: &str = "ART-1778";
-const PTO_ID: &str = "ART-1790";
-
-#[derive(Debug)]
-struct GitCommitOccurance {
-    ticket_id: String,
-    comments: Vec<String>,
-    started: DateTime<Local>,
-}
-
-#[derive(Debug)]
-struct GitCommitTimeEntry {
-    occurance: GitCommitOccurance,
-    remaining_time: Duration,
-}
+pub const PTO_ID: &str = "ART-1790";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum JiraTicketId {
     Regular(String),
-    DailyStandup,
-    Pto,
+    DailyStandup(String),
+    Meeting(String),
+    Absence(AbsenceKind, String),
     Skipped,
 }
 
 impl JiraTicketId {
     pub fn to_str(&self) -> &str {
         match self {
-            Self::Pto => PTO_ID,
-            Self::DailyStandup => DAILY_STANDUP_ID,
-            Self::Regular(str) => str,
+            Self::Absence(_, ticket) => ticket,
+            Self::Regular(str) | Self::DailyStandup(str) | Self::Meeting(str) => str,
             Self::Skipped => "NULL",
         }
     }
+
+    /// Points the entry at another ticket, keeping what kind of entry it is
+    pub fn set_ticket(&mut self, ticket: String) {
+        match self {
+            Self::Regular(str) | Self::DailyStandup(str) | Self::Meeting(str) | Self::Absence(_, str) => *str = ticket,
+            Self::Skipped => {}
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct JiraTimeEntry {
     pub ticket_id: JiraTicketId,
     pub comment: String,
-    pub started: DateTime<Local>,
+    pub started: DateTime<Tz>,
     pub time_spent: Duration,
 }
 
-pub fn construct_jira_payload(
-    aliases: &[String],
-    repos: &[PathBuf],
+/// Which commits count as the user's and where they are read from
+pub struct CommitSource<'a> {
+    pub aliases: &'a [String],
+    pub author_email: &'a str,
+    pub remote: &'a str,
+    /// Each commit becomes a line of the worklog comment rendered with this
+    pub comment_format: &'a CommentFormat,
+    /// Whether commits are placed on the day they were committed or authored
+    pub commit_date: CommitDate,
+    /// Where commits read before are kept between runs, every run walks the history again without it
+    pub cache_dir: Option<&'a Path>,
+}
+
+/// Commits of the user in the range grouped by ticket, oldest first. Reads what was fetched last, see `git_helpers::sync_all`
+/// `repos` are paths with the branch to read in each
+pub fn collect_commits(
+    repos: &[(PathBuf, String)],
+    source: &CommitSource,
     start_date: chrono::NaiveDateTime,
     end_date: chrono::NaiveDateTime,
-    vacation_days: Vec<NaiveDate>,
-    skip_days: Vec<NaiveDate>,
-    should_pull: bool,
-) -> anyhow::Result<Vec<JiraTimeEntry>> {
-    let opened_repos = repos
-        .iter()
-        .map(|path| -> anyhow::Result<Repository> {
-            let full_path = std::fs::canonicalize(path)?;
-            return Ok(Repository::open(full_path.clone())?);
-        })
-        .filter_map(|r| r.ok())
-        .collect::<Vec<_>>();
-
-    if should_pull {
-        for opened_repo in opened_repos.iter() {
-            crate::git_helpers::pull(opened_repo)?;
+    tz: Tz,
+) -> anyhow::Result<Vec<TicketCommits>> {
+    let mut commits = Vec::new();
+    for (path, branch) in repos {
+        let opened = std::fs::canonicalize(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|full_path| Ok(Repository::open(full_path)?));
+
+        let res =
+            opened.and_then(|repo| add_commits_from_repo(&mut commits, source, branch, start_date, end_date, tz, repo));
+        if let Err(err) = res {
+            println!(
+                "       {} skipping {}: {}, see {}",
+                "Warning:".yellow(),
+                path.to_string_lossy(),
+                err,
+                "tempo repo check".green()
+            );
         }
     }
+    commits.sort_unstable_by_key(|c| c.started);
 
-    let mut commits = Vec::new();
-    for repo in opened_repos {
-        add_commits_from_repo(&mut commits, start_date, end_date, aliases, repo)?;
+    return Ok(commits);
+}
+
+/// Whether a commit by `commit_email` is the user's, by their git email or one of their aliases
+pub fn is_author(user_email: &str, aliases: &[String], commit_email: &str) -> bool {
+    if commit_email.contains(user_email) {
+        return true;
     }
-    commits.sort_unstable_by_key(|c| c.started);
 
-    let parsed = parse_ticket_map(commits, start_date.year(), start_date.month(), vacation_days, skip_days);
+    if aliases.iter().any(|a| a.contains(commit_email)) {
+        return true;
+    }
 
-    return Ok(parsed);
+    return false;
 }
 
 fn add_commits_from_repo(
-    commits: &mut Vec<GitCommitOccurance>,
+    commits: &mut Vec<TicketCommits>,
+    source: &CommitSource,
+    branch: &str,
     start: chrono::NaiveDateTime,
     end: chrono::NaiveDateTime,
-    aliases: &[String],
+    tz: Tz,
     repo: Repository,
 ) -> anyhow::Result<()> {
-    let filter_commit = |config_email: &str, commit: &Commit| {
-        let author = commit.author();
-        let a_email = author.email().unwrap();
-
-        if a_email.contains(config_email) {
-            return true;
-        }
-
-        if aliases.iter().any(|a| a.contains(a_email)) {
-            return true;
-        }
-
-        return false;
+    let repo_name = repo_name(&repo);
+    let remote_url = repo
+        .find_remote(source.remote)
+        .ok()
+        .and_then(|r| r.url().map(|u| u.to_string()));
+
+    let since = tz
+        .from_local_datetime(&start)
+        .earliest()
+        .unwrap_or_else(|| tz.from_utc_datetime(&start));
+    let mut cache = match source.cache_dir {
+        Some(dir) => CommitCache::open(dir, &repo, source.remote, branch),
+        None => CommitCache::default(),
     };
+    cache.update(
+        &repo,
+        git_helpers::branch_tip(&repo, source.remote, branch)?,
+        since.timestamp(),
+    )?;
+    if let Err(err) = cache.save() {
+        println!("{} {}", "Failed to save the commit cache:".yellow(), err);
+    }
 
-    let global_cfg = git2::Config::find_global()?;
-    let cfg = git2::Config::open(global_cfg.as_path())?;
-
-    let email_entry = cfg.get_entry("user.email")?;
-    let email = email_entry
-        .value()
-        .ok_or(anyhow::format_err!("Couldnt parse email from global git config"))?;
-
-    let mut rev = repo.revwalk()?;
-    let dev_branch = repo.find_branch("develop", BranchType::Local)?;
-    rev.push(dev_branch.get().target().expect("Weird, branch has no target"))?;
-    rev.set_sorting(Sort::TIME)?;
-
-    for oid in rev.filter_map(|item| item.ok()) {
-        let commit = repo.find_commit(oid)?;
-        let timestamp_utc = chrono::DateTime::from_timestamp(commit.time().seconds(), 0).unwrap();
+    for commit in cache.commits() {
+        let timestamp_utc = unwrap_or_continue!(chrono::DateTime::from_timestamp(commit.time(source.commit_date), 0));
 
-        let timestamp = DateTime::from(timestamp_utc);
+        let timestamp = timestamp_utc.with_timezone(&tz);
         let timestamp_naive = timestamp.naive_local();
 
-        if timestamp_naive > end {
+        if timestamp_naive > end || timestamp_naive < start {
             continue;
         }
 
-        if timestamp_naive < start {
-            break;
-        }
-
-        if !filter_commit(email, &commit) {
+        if !is_author(source.author_email, source.aliases, &commit.email) {
             continue;
         }
 
-        if commit.message().is_none() {
-            continue;
-        }
-
-        let msg_ref = unwrap_or_continue!(commit.message());
-        let semi_idx = unwrap_or_continue!(msg_ref.find(':'));
-
-        let ticket_id = msg_ref[..semi_idx].trim().to_string();
-        let comment = msg_ref[(semi_idx + 1)..].trim().to_string();
+        let comment = source.comment_format.render(&CommitInfo {
+            repo: repo_name.clone(),
+            subject: commit.subject.clone(),
+            branch: branch.to_string(),
+            url: remote_url.as_deref().and_then(|url| commit_url(url, &commit.oid)),
+            sha: commit.oid.clone(),
+        });
 
-        match commits.iter_mut().find(|item| item.ticket_id == ticket_id) {
+        match commits.iter_mut().find(|item| item.ticket_id == commit.ticket) {
             Some(val) => {
                 val.comments.push(comment);
             }
             None => {
-                commits.push(GitCommitOccurance {
+                commits.push(TicketCommits {
                     started: timestamp,
-                    ticket_id,
+                    ticket_id: commit.ticket.clone(),
                     comments: vec![comment],
                 });
             }
@@ -164,107 +176,14 @@ fn add_commits_from_repo(
     return Ok(());
 }
 
-fn parse_ticket_map(
-    commits: Vec<GitCommitOccurance>,
-    year: i32,
-    month: u32,
-    vacation_days: Vec<NaiveDate>,
-    skip_days: Vec<NaiveDate>,
-) -> Vec<JiraTimeEntry> {
-    let last_day = time::last_day_of_month(year, month);
-    let mut res = vec![];
-
-    let days = (0..last_day.day())
-        .rev()
-        .map(|i| last_day - Duration::days(i as i64))
-        .filter(|day| !matches!(day.weekday(), Weekday::Sun | Weekday::Sat))
-        .collect::<Vec<_>>();
-
-    let total_required_duration = Duration::minutes((days.len() * 8 * 60) as i64);
-    let each_ticket_duration = total_required_duration / commits.len() as i32;
-
-    res.reserve(days.len() + commits.len());
-
-    let mut commit_entries = commits
-        .into_iter()
-        .map(|c| GitCommitTimeEntry {
-            occurance: c,
-            remaining_time: each_ticket_duration,
-        })
-        .peekable();
-
-    let daily_standup_duration = Duration::minutes(30);
-
-    let mut total_logged = Duration::zero();
-
-    for day in days.iter() {
-        if skip_days.iter().any(|d| *d == *day) {
-            res.push(JiraTimeEntry {
-                ticket_id: JiraTicketId::Skipped,
-                started: Local::now(),
-                comment: String::from("empty"),
-                time_spent: Duration::zero(),
-            });
-
-            continue;
-        }
-
-        if vacation_days.iter().any(|d| *d == *day) {
-            let vacation_date_utc = Utc
-                .from_utc_datetime(&day.and_time(NaiveTime::from_hms_opt(9, 0, 0).unwrap()))
-                .with_timezone(&Local);
-
-            res.push(JiraTimeEntry {
-                ticket_id: JiraTicketId::Pto,
-                comment: "(Auto generated) PTO".to_string(),
-                started: vacation_date_utc,
-                time_spent: Duration::hours(8),
-            });
-
-            continue;
-        }
-
-        let daily_date_utc = Utc
-            .from_utc_datetime(&day.and_time(NaiveTime::from_hms_opt(15, 0, 0).unwrap()))
-            .with_timezone(&Local);
-
-        res.push(JiraTimeEntry {
-            ticket_id: JiraTicketId::DailyStandup,
-            time_spent: daily_standup_duration,
-            comment: "(Auto generated) Daily standup".to_string(),
-            started: daily_date_utc,
-        });
-        total_logged = total_logged + daily_standup_duration;
-
-        if total_logged >= total_required_duration {
-            continue;
-        }
-
-        let next_commit = match commit_entries.peek_mut() {
-            Some(c) => {
-                if c.remaining_time > Duration::zero() {
-                    c
-                } else {
-                    commit_entries.next();
-                    match commit_entries.peek_mut() {
-                        Some(c) => c,
-                        None => continue,
-                    }
-                }
-            }
-            None => continue,
-        };
-
-        let spent = Duration::hours(7).add(Duration::minutes(30));
-        res.push(JiraTimeEntry {
-            ticket_id: JiraTicketId::Regular(next_commit.occurance.ticket_id.clone()),
-            time_spent: spent,
-            comment: format!("(Auto generated) \n{}", next_commit.occurance.comments.join("\n")),
-            started: daily_date_utc + Duration::minutes(30),
-        });
-        next_commit.remaining_time = next_commit.remaining_time - spent;
-        total_logged = total_logged + spent;
-    }
+/// Name of the folder the repo is checked out in, without .git for bare repos
+fn repo_name(repo: &Repository) -> String {
+    let path = repo.workdir().unwrap_or(repo.path());
 
-    res
+    path.components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .rfind(|c| *c != ".git")
+        .unwrap_or("")
+        .trim_end_matches(".git")
+        .to_string()
 }
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt::Formatter;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MeetingField {
+    Title,
+    Organizer,
+}
+
+/// Logs calendar events whose title or organizer contains `pattern` to `ticket`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MeetingRule {
+    pub field: MeetingField,
+    pub pattern: String,
+    pub ticket: String,
+}
+
+impl MeetingRule {
+    pub fn matches(&self, title: &str, organizer: &str) -> bool {
+        let haystack = match self.field {
+            MeetingField::Title => title,
+            MeetingField::Organizer => organizer,
+        };
+
+        haystack.to_lowercase().contains(&self.pattern.to_lowercase())
+    }
+}
+
+impl std::fmt::Display for MeetingRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let field = match self.field {
+            MeetingField::Title => "title",
+            MeetingField::Organizer => "organizer",
+        };
+
+        write!(f, "{} contains \"{}\" -> {}", field, self.pattern, self.ticket)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Meeting {
+    pub summary: String,
+    pub ticket: String,
+    pub started: DateTime<Tz>,
+    pub duration: Duration,
+}
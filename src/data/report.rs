@@ -0,0 +1,194 @@
+use super::{get_app_dir, JiraTicketId, JiraTimeEntry};
+use crate::jira::PublishOutcome;
+use anyhow::Context;
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryStatus {
+    Created,
+    Skipped,
+    Failed,
+    /// Created, then removed again by `tempo undo`
+    Deleted,
+    /// Timed out, check jira before publishing it again
+    Unknown,
+    /// Not answered yet, or the run was interrupted before it was
+    Pending,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportEntry {
+    pub ticket: String,
+    pub started: DateTime<FixedOffset>,
+    pub time_spent_seconds: i64,
+    pub comment: String,
+    pub status: EntryStatus,
+    pub http_status: Option<u16>,
+    pub worklog_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ReportEntry {
+    pub fn time_spent(&self) -> Duration {
+        Duration::seconds(self.time_spent_seconds)
+    }
+}
+
+/// Outcome of a single `tempo publish`, saved as json under ~/.tempo/history
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublishReport {
+    pub id: String,
+    pub created_at: DateTime<FixedOffset>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub entries: Vec<ReportEntry>,
+}
+
+fn history_dir() -> PathBuf {
+    get_app_dir().join("history")
+}
+
+impl PublishReport {
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Self {
+        let now = Local::now();
+
+        Self {
+            id: now.format("%Y%m%d-%H%M%S").to_string(),
+            created_at: now.fixed_offset(),
+            from,
+            to,
+            entries: vec![],
+        }
+    }
+
+    /// Adds an entry, one without an outcome yet is pending unless it's skipped
+    pub fn add(&mut self, entry: &JiraTimeEntry, outcome: Option<&PublishOutcome>) {
+        let status = match entry.ticket_id {
+            JiraTicketId::Skipped => EntryStatus::Skipped,
+            _ => EntryStatus::Pending,
+        };
+
+        self.entries.push(ReportEntry {
+            ticket: entry.ticket_id.to_str().to_string(),
+            started: entry.started.fixed_offset(),
+            time_spent_seconds: entry.time_spent.num_seconds(),
+            comment: entry.comment.clone(),
+            status,
+            http_status: None,
+            worklog_id: None,
+            error: None,
+        });
+
+        if let (Some(outcome), EntryStatus::Pending) = (outcome, status) {
+            self.record(self.entries.len() - 1, outcome);
+        }
+    }
+
+    /// Fills in the answer jira gave for the entry at `idx`
+    pub fn record(&mut self, idx: usize, outcome: &PublishOutcome) {
+        let entry = &mut self.entries[idx];
+
+        entry.status = if outcome.is_success() {
+            EntryStatus::Created
+        } else if outcome.unknown {
+            EntryStatus::Unknown
+        } else {
+            EntryStatus::Failed
+        };
+        entry.http_status = outcome.status;
+        entry.worklog_id = outcome.worklog_id.clone();
+        entry.error = outcome.error.clone();
+    }
+
+    /// Entries that were created and can still be deleted
+    pub fn undoable(&self) -> impl Iterator<Item = &ReportEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == EntryStatus::Created && e.worklog_id.is_some())
+    }
+
+    pub fn count(&self, status: EntryStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    pub fn time_spent(&self, status: EntryStatus) -> Duration {
+        self.entries
+            .iter()
+            .filter(|e| e.status == status)
+            .fold(Duration::zero(), |acc, e| acc + e.time_spent())
+    }
+
+    pub fn path(&self) -> PathBuf {
+        history_dir().join(format!("{}.json", self.id))
+    }
+
+    /// First save of a run, adds a suffix to the id when another run started in the same second
+    pub fn save_new(&mut self) -> anyhow::Result<PathBuf> {
+        std::fs::create_dir_all(history_dir())?;
+
+        let base = self.id.clone();
+        for n in 1.. {
+            if n > 1 {
+                self.id = format!("{}-{}", base, n);
+            }
+
+            // create_new claims the id, even against a run saving at the same moment
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.path())
+            {
+                Ok(_) => return self.save(),
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err).with_context(|| format!("Failed to save {}", self.path().display())),
+            }
+        }
+
+        unreachable!()
+    }
+
+    pub fn save(&self) -> anyhow::Result<PathBuf> {
+        std::fs::create_dir_all(history_dir())?;
+
+        let path = self.path();
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to save {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    pub fn load(id: &str) -> anyhow::Result<Self> {
+        let path = history_dir().join(format!("{}.json", id));
+        let content = std::fs::read(&path).with_context(|| format!("No publish run {}", id))?;
+
+        serde_json::from_slice(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// All saved runs, oldest first
+    pub fn list() -> anyhow::Result<Vec<Self>> {
+        let dir = history_dir();
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut res = vec![];
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let content = std::fs::read(&path)?;
+            match serde_json::from_slice::<Self>(&content) {
+                Ok(report) => res.push(report),
+                Err(err) => println!("Skipping {}: {}", path.display(), err),
+            }
+        }
+        res.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+        Ok(res)
+    }
+}
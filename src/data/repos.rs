@@ -1,12 +1,85 @@
-use crate::data::{DirtyTracker, OnDataInit};
+use crate::data::{get_app_dir, DirtyTracker, OnDataInit};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
 pub struct Repos
 {
+    #[serde(skip)]
     is_dirty: bool,
     pub list: Vec<PathBuf>,
+    pub roots: Vec<WatchedRoot>,
+    pub mirrors: Vec<Mirror>,
+}
+
+impl Repos
+{
+    /// Every repo commits are read from with the branch to read, local ones first, then mirrors
+    pub fn tracked(&self, default_branch: &str) -> Vec<(PathBuf, String)>
+    {
+        let mirrors = self.mirrors.iter().map(|m| {
+            let branch = m.branch.as_deref().unwrap_or(default_branch);
+            (m.path.clone(), branch.to_string())
+        });
+
+        self.list
+            .iter()
+            .map(|path| (path.clone(), default_branch.to_string()))
+            .chain(mirrors)
+            .collect()
+    }
+}
+
+/// Bare copy of a repo that's never cloned locally, see `tempo repo add --remote`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Mirror
+{
+    pub url: String,
+    pub path: PathBuf,
+    /// Branch commits are read from, the configured git branch when not set
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+/// Where the mirror of `url` lives, eg. ~/.tempo/mirrors/github.com/org/infra.git.
+/// The https and ssh urls of a repo share it.
+pub fn mirror_dir(url: &str) -> PathBuf
+{
+    let url = url.trim().trim_end_matches('/').trim_end_matches(".git");
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = match url.split_once('@')
+    {
+        Some((user, rest)) if !user.contains('/') => rest,
+        _ => url,
+    };
+
+    let relative = url
+        .replace(':', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+        .collect::<Vec<_>>()
+        .join("/");
+
+    get_app_dir().join("mirrors").join(format!("{}.git", relative))
+}
+
+/// Folder new clones are picked up from on publish, see `tempo repo scan --watch`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchedRoot
+{
+    pub path: PathBuf,
+    pub depth: usize,
+    /// Repos found under it before, tracked or not, only the others are new
+    pub seen: Vec<PathBuf>,
+}
+
+/// Layout of the bincode file written before the repos were stored as json
+#[derive(Deserialize)]
+struct LegacyRepos
+{
+    _is_dirty: bool,
+    list: Vec<PathBuf>,
 }
 
 impl DirtyTracker for Repos
@@ -23,5 +96,80 @@ impl DirtyTracker for Repos
 
 impl OnDataInit for Repos
 {
+    fn from_legacy(content: &[u8]) -> Option<Self>
+    {
+        let legacy = bincode::deserialize::<LegacyRepos>(content).ok()?;
+
+        Some(Self {
+            list: legacy.list,
+            ..Default::default()
+        })
+    }
+
     fn on_init(&mut self) {}
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn mirror_dirs_follow_the_url()
+    {
+        let mirrors = get_app_dir().join("mirrors");
+
+        assert_eq!(mirror_dir("git@github.com:org/infra.git"), mirrors.join("github.com/org/infra.git"));
+        assert_eq!(mirror_dir("https://user@gitlab.com/group/docs/"), mirrors.join("gitlab.com/group/docs.git"));
+        assert_eq!(mirror_dir("ssh://git@host:2222/../team/repo"), mirrors.join("host/2222/team/repo.git"));
+        assert_eq!(mirror_dir("https://github.com/org/infra"), mirror_dir("git@github.com:org/infra.git"));
+    }
+
+    #[test]
+    fn reads_older_repo_files()
+    {
+        #[derive(Serialize)]
+        struct OldRepos
+        {
+            is_dirty: bool,
+            list: Vec<PathBuf>,
+        }
+
+        let old = bincode::serialize(&OldRepos {
+            is_dirty: true,
+            list: vec![PathBuf::from("/work/api")],
+        })
+        .unwrap();
+        let repos = Repos::from_legacy(&old).unwrap();
+        assert_eq!(repos.list, vec![PathBuf::from("/work/api")]);
+        assert!(repos.roots.is_empty() && repos.mirrors.is_empty());
+
+        let repos = serde_json::from_str::<Repos>(r#"{"list": ["/work/api"]}"#).unwrap();
+        assert_eq!(repos.tracked("develop"), vec![(PathBuf::from("/work/api"), String::from("develop"))]);
+    }
+
+    #[test]
+    fn mirrors_read_their_own_branch()
+    {
+        let mirror = |url: &str, branch: Option<&str>| Mirror {
+            url: url.to_string(),
+            path: mirror_dir(url),
+            branch: branch.map(String::from),
+        };
+        let repos = Repos {
+            list: vec![PathBuf::from("/work/api")],
+            mirrors: vec![
+                mirror("git@github.com:org/infra.git", Some("main")),
+                mirror("git@github.com:org/docs.git", None),
+            ],
+            ..Default::default()
+        };
+
+        let branches = repos
+            .tracked("develop")
+            .into_iter()
+            .map(|(_, branch)| branch)
+            .collect::<Vec<_>>();
+        assert_eq!(branches, ["develop", "main", "develop"]);
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// Minutes of work for each weekday, starting with Monday
+pub type WeekMinutes = [i64; 7];
+
+const FULL_WEEK: WeekMinutes = [8 * 60, 8 * 60, 8 * 60, 8 * 60, 8 * 60, 0, 0];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WorkSchedule {
+    pub week: WeekMinutes,
+    pub start_time: NaiveTime,
+    pub standup_minutes: i64,
+    pub overrides: Vec<ScheduleOverride>,
+}
+
+/// Replaces the regular week between `from` and `to` (inclusive), eg. a part-time period or a half-day
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleOverride {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub week: WeekMinutes,
+}
+
+impl Default for WorkSchedule {
+    fn default() -> Self {
+        Self {
+            week: FULL_WEEK,
+            start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            standup_minutes: 30,
+            overrides: vec![],
+        }
+    }
+}
+
+impl WorkSchedule {
+    /// Scheduled working time for the given day, the most recently added override wins
+    pub fn capacity(&self, day: NaiveDate) -> Duration {
+        let week = self
+            .overrides
+            .iter()
+            .rev()
+            .find(|o| o.from <= day && day <= o.to)
+            .map(|o| &o.week)
+            .unwrap_or(&self.week);
+
+        Duration::minutes(week[day.weekday().num_days_from_monday() as usize])
+    }
+
+    pub fn standup(&self) -> Duration {
+        Duration::minutes(self.standup_minutes)
+    }
+}
+
+/// Parses "8,8,8,8,6,0,0" (Monday to Sunday) or "8,8,8,8,6" (Monday to Friday) as hours per weekday
+pub fn parse_week(val: &str) -> anyhow::Result<WeekMinutes> {
+    let hours = val
+        .split(',')
+        .map(|h| h.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if hours.len() != 5 && hours.len() != 7 {
+        anyhow::bail!("Expected hours for 5 or 7 days, got {}", hours.len());
+    }
+
+    if hours.iter().any(|h| *h < 0.0 || *h > 24.0) {
+        anyhow::bail!("Hours must be between 0 and 24");
+    }
+
+    let mut week = [0; 7];
+    for (idx, h) in hours.into_iter().enumerate() {
+        week[idx] = (h * 60.0).round() as i64;
+    }
+
+    Ok(week)
+}
+
+pub fn format_week(week: &WeekMinutes) -> String {
+    week.iter()
+        .map(|m| format!("{}", *m as f32 / 60.0))
+        .collect::<Vec<_>>()
+        .join(",")
+}
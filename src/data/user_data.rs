@@ -1,7 +1,12 @@
 use super::*;
+use crate::git_helpers::GitCredentials;
+use crate::time::SprintCadence;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use colored::Colorize;
 use paste::paste;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
 
 macro_rules! add_getters {
     (struct $name:ident { $($v:vis $fname:ident : Option<$ftype:ty>),* $(,)? }) => {
@@ -44,15 +49,118 @@ macro_rules! add_getters {
 add_getters! {
 struct UserData {
     jira_token: Option<String>,
+    jira_url: Option<String>,
     pub user_aliases: Option<Vec<String>>,
+    pub work_schedule: Option<WorkSchedule>,
+    sprint_start: Option<NaiveDate>,
+    sprint_weeks: Option<u32>,
+    timezone: Option<String>,
+    pub absence_tickets: Option<HashMap<AbsenceKind, String>>,
+    pub absences_path: Option<PathBuf>,
+    pub calendar_path: Option<PathBuf>,
+    pub meeting_rules: Option<Vec<MeetingRule>>,
+    pub fallback_ticket: Option<String>,
+    pub comment_format: Option<CommentFormat>,
+    git_remote: Option<String>,
+    git_branch: Option<String>,
+    pub ssh_keys: Option<Vec<PathBuf>>,
+    git_token: Option<String>,
+    commit_date: Option<CommitDate>,
 }
 }
 
+impl UserData {
+    pub fn sprint(&self) -> Option<SprintCadence> {
+        Some(SprintCadence {
+            start: self.sprint_start?,
+            weeks: self.sprint_weeks?,
+        })
+    }
+
+    pub fn git_credentials(&self) -> GitCredentials {
+        GitCredentials {
+            ssh_keys: self.get_ssh_keys().clone(),
+            https_token: self.git_token.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// IANA timezone worklog start times are expressed in
+    pub fn tz(&self) -> anyhow::Result<Tz> {
+        let name = self.get_timezone();
+
+        name.parse::<Tz>()
+            .map_err(|e| anyhow::anyhow!("Invalid timezone {}: {}", name, e))
+    }
+}
+
+/// Layout of the bincode file written before the user data was stored as json
+#[derive(Deserialize)]
+struct LegacyUserData {
+    jira_token: Option<String>,
+    user_aliases: Option<Vec<String>>,
+}
+
 impl OnDataInit for UserData {
+    fn from_legacy(content: &[u8]) -> Option<Self> {
+        let legacy = bincode::deserialize::<LegacyUserData>(content).ok()?;
+
+        Some(Self {
+            jira_token: legacy.jira_token,
+            user_aliases: legacy.user_aliases,
+            ..Default::default()
+        })
+    }
+
     fn on_init(&mut self) {
+        if self.jira_url.is_none() {
+            self.jira_url = Some(String::from("https://jira.internetbrands.com"));
+        }
+
         if self.user_aliases.is_none() {
             self.user_aliases = Some(vec![]);
         }
+
+        if self.work_schedule.is_none() {
+            self.work_schedule = Some(WorkSchedule::default());
+        }
+
+        if self.absence_tickets.is_none() {
+            self.absence_tickets = Some(
+                AbsenceKind::all()
+                    .into_iter()
+                    .map(|k| (k, PTO_ID.to_string()))
+                    .collect(),
+            );
+        }
+
+        if self.git_remote.is_none() {
+            self.git_remote = Some(String::from("origin"));
+        }
+
+        if self.git_branch.is_none() {
+            self.git_branch = Some(String::from("develop"));
+        }
+
+        if self.commit_date.is_none() {
+            self.commit_date = Some(CommitDate::default());
+        }
+
+        if self.ssh_keys.is_none() {
+            self.ssh_keys = Some(vec![]);
+        }
+
+        if self.comment_format.is_none() {
+            self.comment_format = Some(CommentFormat::default());
+        }
+
+        if self.meeting_rules.is_none() {
+            self.meeting_rules = Some(vec![]);
+        }
+
+        if self.timezone.is_none() {
+            self.timezone = Some(iana_time_zone::get_timezone().unwrap_or(String::from("UTC")));
+        }
     }
 }
 impl DirtyTracker for UserData {
@@ -64,3 +172,46 @@ impl DirtyTracker for UserData {
         self.is_dirty = true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct OldUserData {
+        jira_token: Option<String>,
+        user_aliases: Option<Vec<String>>,
+    }
+
+    #[test]
+    fn reads_the_old_bincode_file() {
+        let old = bincode::serialize(&OldUserData {
+            jira_token: Some(String::from("secret")),
+            user_aliases: Some(vec![String::from("me@home.dev")]),
+        })
+        .unwrap();
+
+        assert!(serde_json::from_slice::<UserData>(&old).is_err());
+
+        let data = UserData::from_legacy(&old).unwrap();
+        assert_eq!(data.jira_token.as_deref(), Some("secret"));
+        assert_eq!(data.get_user_aliases(), &vec![String::from("me@home.dev")]);
+        assert!(data.work_schedule.is_none());
+    }
+
+    #[test]
+    fn json_round_trips_and_tolerates_missing_fields() {
+        let mut data = UserData::default();
+        data.on_init();
+        data.set_jira_token(String::from("secret"));
+
+        let json = serde_json::to_vec(&data).unwrap();
+        let read = serde_json::from_slice::<UserData>(&json).unwrap();
+        assert_eq!(read.get_jira_token(), "secret");
+        assert_eq!(read.get_git_branch(), "develop");
+
+        let partial = serde_json::from_str::<UserData>(r#"{"jira_token": "secret"}"#).unwrap();
+        assert_eq!(partial.get_jira_token(), "secret");
+        assert!(partial.git_branch.is_none());
+    }
+}
@@ -4,7 +4,7 @@ use serde::Serialize;
 use std::fmt::Debug;
 use std::path::PathBuf;
 
-fn get_app_dir() -> PathBuf {
+pub fn get_app_dir() -> PathBuf {
     let home_dir_path = std::env::var("HOME").unwrap();
 
     PathBuf::from(home_dir_path).join(".tempo")
@@ -17,6 +17,14 @@ pub trait DirtyTracker {
 
 pub trait OnDataInit {
     fn on_init(&mut self);
+
+    /// Reads a file written by older versions, which stored the data as bincode
+    fn from_legacy(_content: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -71,7 +79,14 @@ impl<T: Serialize + DeserializeOwned + Default + DirtyTracker + Debug + OnDataIn
             return self.inner();
         }
 
-        match bincode::deserialize::<T>(file_content.as_slice()) {
+        // Files from older versions are converted and written back as json on the next save
+        let parsed = serde_json::from_slice::<T>(file_content.as_slice()).or_else(|err| {
+            let mut legacy = T::from_legacy(file_content.as_slice()).ok_or(err)?;
+            legacy.set_dirty();
+            Ok::<_, serde_json::Error>(legacy)
+        });
+
+        match parsed {
             Ok(val) => {
                 self.inner = Some(val);
             }
@@ -100,7 +115,7 @@ impl<T: Serialize + DeserializeOwned + Default + DirtyTracker + Debug + OnDataIn
         if self.inner.is_none() || !self.inner().is_dirty() {
             return;
         }
-        let data = bincode::serialize(self.inner.as_ref().unwrap()).expect("Failed to serialize user data");
+        let data = serde_json::to_vec_pretty(self.inner.as_ref().unwrap()).expect("Failed to serialize user data");
 
         std::fs::write(self.path.clone(), data.as_slice()).expect("Couldnt save user data file");
     }
@@ -1,45 +1,266 @@
-use git2::{Cred, RemoteCallbacks, Repository};
-use std::env;
-use std::io::Write;
+mod credentials;
 
-const REMOTE_BRANCH: &str = "develop";
+pub use credentials::{is_ssh_url, GitCredentials};
 
-pub fn pull(repo: &Repository) -> anyhow::Result<()> {
-    let fetch_commit = do_fetch(repo, REMOTE_BRANCH)?;
-    do_merge(repo, REMOTE_BRANCH, fetch_commit)?;
+use crate::{data, pretty_print};
+use colored::Colorize;
+use git2::{RemoteCallbacks, Repository};
+use indicatif::{MultiProgress, ProgressBar};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// What happens to a repo before commits are read from it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GitSync {
+    /// Read whatever was fetched last
+    Offline,
+    /// Update the remote-tracking branch only, local branches and the working tree stay untouched
+    Fetch,
+    /// Fetch and merge into the local branch, checking it out
+    Pull,
+}
+
+/// user.email from the global git config, commits by this author are logged
+pub fn global_email() -> anyhow::Result<String> {
+    let global_cfg = git2::Config::find_global()?;
+    let cfg = git2::Config::open(global_cfg.as_path())?;
+
+    let email_entry = cfg.get_entry("user.email")?;
+    let email = email_entry
+        .value()
+        .ok_or(anyhow::format_err!("Couldnt parse email from global git config"))?;
+
+    Ok(email.to_string())
+}
+
+/// Git repos in `root` and its folders up to `depth` levels down, without looking inside repos or hidden folders
+pub fn find_repos(root: &Path, depth: usize) -> Vec<PathBuf> {
+    let mut found = vec![];
+    let mut pending = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, level)) = pending.pop() {
+        if dir.join(".git").exists() {
+            found.push(dir);
+            continue;
+        }
+
+        if level >= depth {
+            continue;
+        }
+
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(val) => val,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if !hidden && entry.path().is_dir() {
+                pending.push((entry.path(), level + 1));
+            }
+        }
+    }
+    found.sort();
+
+    found
+}
+
+/// Commits from `tip` since the unix timestamp `since` whose author email passes `is_users`
+pub fn count_commits_since(
+    repo: &Repository,
+    tip: git2::Oid,
+    since: i64,
+    is_users: impl Fn(&str) -> bool,
+) -> anyhow::Result<usize> {
+    let mut count = 0;
+    data::walk_since(repo, tip, since, |commit| {
+        if is_users(commit.author().email().unwrap_or("")) {
+            count += 1;
+        }
+    })?;
+
+    Ok(count)
+}
+
+/// How a single fetch reports progress and when it gives up
+pub struct FetchContext<'a> {
+    pub credentials: &'a GitCredentials,
+    pub progress: ProgressBar,
+    pub deadline: Instant,
+}
+
+impl<'a> FetchContext<'a> {
+    /// No progress output and no time limit
+    #[allow(unused)]
+    pub fn quiet(credentials: &'a GitCredentials) -> Self {
+        Self {
+            credentials,
+            progress: ProgressBar::hidden(),
+            deadline: Instant::now() + Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+pub fn sync(repo: &Repository, remote: &str, branch: &str, mode: GitSync, ctx: &FetchContext) -> anyhow::Result<()> {
+    match mode {
+        GitSync::Offline => Ok(()),
+        GitSync::Fetch => fetch(repo, remote, branch, ctx),
+        // Mirrors have nothing checked out to merge into
+        GitSync::Pull if repo.is_bare() => fetch(repo, remote, branch, ctx),
+        GitSync::Pull => pull(repo, remote, branch, ctx),
+    }
+}
+
+/// Syncs every repo on its own thread with a progress bar each, returns the repos that failed or timed out.
+/// A repo still fetching at the timeout is left to finish in the background. `repos` are paths with the branch of each.
+pub fn sync_all(
+    repos: &[(PathBuf, String)],
+    remote: &str,
+    mode: GitSync,
+    credentials: &GitCredentials,
+    timeout: Duration,
+) -> Vec<(PathBuf, String)> {
+    if mode == GitSync::Offline || repos.is_empty() {
+        return vec![];
+    }
+
+    // Fetch threads can't prompt, passphrases have to be known before they start
+    let mut credentials = credentials.clone();
+    if repos
+        .iter()
+        .any(|(path, _)| remote_url(path, remote).is_some_and(|url| is_ssh_url(&url)))
+    {
+        credentials.unlock_keys();
+    }
+
+    let mp = MultiProgress::new();
+    let deadline = Instant::now() + timeout;
+    let (tx, rx) = mpsc::channel();
+
+    let bars = repos
+        .iter()
+        .enumerate()
+        .map(|(idx, (path, branch))| {
+            let pb = mp.add(pretty_print::get_fetch_bar(&path.to_string_lossy()));
+            let (tx, path, pb_thread) = (tx.clone(), path.clone(), pb.clone());
+            let (remote, branch, credentials) = (remote.to_string(), branch.clone(), credentials.clone());
+
+            std::thread::spawn(move || {
+                let ctx = FetchContext {
+                    credentials: &credentials,
+                    progress: pb_thread,
+                    deadline,
+                };
+                let res = Repository::open(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|repo| sync(&repo, &remote, &branch, mode, &ctx));
+
+                let _ = tx.send((idx, res.map_err(|e| e.to_string())));
+            });
+
+            pb
+        })
+        .collect::<Vec<_>>();
+    drop(tx);
+
+    let mut results = repos.iter().map(|_| None).collect::<Vec<_>>();
+    while results.iter().any(Option::is_none) {
+        let (idx, res) = match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(val) => val,
+            Err(_) => break,
+        };
+
+        match &res {
+            Ok(_) => bars[idx].finish_with_message("done".green().to_string()),
+            Err(err) => bars[idx].abandon_with_message(err.bright_red().to_string()),
+        }
+        results[idx] = Some(res);
+    }
+
+    let mut failed = vec![];
+    for (idx, res) in results.into_iter().enumerate() {
+        match res {
+            Some(Ok(_)) => {}
+            Some(Err(err)) => failed.push((repos[idx].0.clone(), err)),
+            None => {
+                bars[idx].abandon_with_message("timed out".bright_red().to_string());
+                failed.push((repos[idx].0.clone(), format!("Timed out after {}s", timeout.as_secs())));
+            }
+        }
+    }
+
+    failed
+}
+
+/// Url of `remote` of the repo at `path`, None when it can't be read
+fn remote_url(path: &Path, remote: &str) -> Option<String> {
+    let repo = Repository::open(path).ok()?;
+    let remote = repo.find_remote(remote).ok()?;
+
+    remote.url().map(|url| url.to_string())
+}
+
+/// Creates a bare repo at `path` with `url` as `remote` and fetches `branch` into it
+pub fn create_mirror(path: &Path, url: &str, remote: &str, branch: &str, ctx: &FetchContext) -> anyhow::Result<()> {
+    let repo = Repository::init_bare(path)?;
+    repo.remote(remote, url)?;
+
+    fetch(&repo, remote, branch, ctx)
+}
+
+/// Updates refs/remotes/<remote>/<branch> and nothing else
+pub fn fetch(repo: &Repository, remote: &str, branch: &str, ctx: &FetchContext) -> anyhow::Result<()> {
+    let refspec = format!("+refs/heads/{}:refs/remotes/{}/{}", branch, remote, branch);
+    do_fetch(repo, remote, &refspec, ctx)?;
+
+    Ok(())
+}
+
+pub fn pull(repo: &Repository, remote: &str, branch: &str, ctx: &FetchContext) -> anyhow::Result<()> {
+    let fetch_commit = do_fetch(repo, remote, branch, ctx)?;
+    do_merge(repo, branch, fetch_commit)?;
 
     Ok(())
 }
 
-fn do_fetch<'a>(repo: &'a Repository, branch: &str) -> anyhow::Result<git2::AnnotatedCommit<'a>> {
-    const REMOTE_NAME: &str = "origin";
+/// Tip commit to read history from: the remote-tracking branch, or the local one when it was never fetched
+pub fn branch_tip(repo: &Repository, remote: &str, branch: &str) -> anyhow::Result<git2::Oid> {
+    let remote_ref = format!("refs/remotes/{}/{}", remote, branch);
+    let local_ref = format!("refs/heads/{}", branch);
 
+    let reference = repo
+        .find_reference(&remote_ref)
+        .or_else(|_| repo.find_reference(&local_ref))
+        .map_err(|_| anyhow::anyhow!("Neither {}/{} nor {} exist", remote, branch, branch))?;
+
+    Ok(reference.peel_to_commit()?.id())
+}
+
+fn do_fetch<'a>(
+    repo: &'a Repository,
+    remote_name: &str,
+    refspec: &str,
+    ctx: &FetchContext,
+) -> anyhow::Result<git2::AnnotatedCommit<'a>> {
     let mut cb = RemoteCallbacks::new();
+    let pb = &ctx.progress;
 
-    cb.credentials(|_url, username_from_url, _allowed_types| {
-        Cred::ssh_key(
-            username_from_url.unwrap(),
-            None,
-            std::path::Path::new(&format!("{}/.ssh/id_ed25519", env::var("HOME").unwrap())),
-            None,
-        )
-    });
+    cb.credentials(ctx.credentials.callback());
 
-    // Print out our transfer progress.
+    // Returning false cancels the transfer, that's how the timeout stops a slow fetch
     cb.transfer_progress(|stats| {
         if stats.received_objects() == stats.total_objects() {
-            print!("Resolving deltas {}/{}\r", stats.indexed_deltas(), stats.total_deltas());
+            pb.set_message(format!(
+                "resolving deltas {}/{}",
+                stats.indexed_deltas(),
+                stats.total_deltas()
+            ));
         } else if stats.total_objects() > 0 {
-            print!(
-                "Received {}/{} objects ({}) in {} bytes\r",
-                stats.received_objects(),
-                stats.total_objects(),
-                stats.indexed_objects(),
-                stats.received_bytes()
-            );
+            pb.set_length(stats.total_objects() as u64);
+            pb.set_position(stats.received_objects() as u64);
         }
-        std::io::stdout().flush().unwrap();
-        true
+
+        Instant::now() < ctx.deadline
     });
 
     let mut fo = git2::FetchOptions::new();
@@ -47,31 +268,18 @@ fn do_fetch<'a>(repo: &'a Repository, branch: &str) -> anyhow::Result<git2::Anno
     // Always fetch all tags.
     // Perform a download and also update tips
     fo.download_tags(git2::AutotagOption::All);
-    println!("Fetching {} for repo {}", branch, repo.path().to_str().unwrap());
-    let mut remote = repo.find_remote(REMOTE_NAME)?;
+    pb.set_message(format!("fetching {}", refspec));
+    let mut remote = repo.find_remote(remote_name)?;
 
-    remote.fetch(&[branch], Some(&mut fo), None)?;
+    remote.fetch(&[refspec], Some(&mut fo), None)?;
 
-    // If there are local objects (we got a thin pack), then tell the user
-    // how many objects we saved from having to cross the network.
     let stats = remote.stats();
-    if stats.local_objects() > 0 {
-        println!(
-            "\rReceived {}/{} objects in {} bytes (used {} local \
-             objects)",
-            stats.indexed_objects(),
-            stats.total_objects(),
-            stats.received_bytes(),
-            stats.local_objects()
-        );
-    } else {
-        println!(
-            "\rReceived {}/{} objects in {} bytes",
-            stats.indexed_objects(),
-            stats.total_objects(),
-            stats.received_bytes()
-        );
-    }
+    pb.set_message(format!(
+        "received {}/{} objects in {} bytes",
+        stats.indexed_objects(),
+        stats.total_objects(),
+        stats.received_bytes()
+    ));
 
     let fetch_head = repo.find_reference("FETCH_HEAD")?;
     Ok(repo.reference_to_annotated_commit(&fetch_head)?)
@@ -176,3 +384,158 @@ fn do_merge<'a>(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FixtureRepo;
+    use chrono::{TimeZone, Utc};
+
+    fn head_of(repo: &Repository, name: &str) -> git2::Oid {
+        repo.find_reference(name).unwrap().peel_to_commit().unwrap().id()
+    }
+
+    #[test]
+    fn fetch_only_moves_the_remote_tracking_branch() {
+        let origin = FixtureRepo::new();
+        let first = origin.commit(
+            "ART-1: First",
+            "dev@example.com",
+            Utc.with_ymd_and_hms(2026, 9, 14, 8, 0, 0).unwrap(),
+        );
+
+        let local = FixtureRepo::clone_of(&origin);
+        let dirty = local.path().join("notes.txt");
+        std::fs::write(&dirty, "uncommitted").unwrap();
+
+        let second = origin.commit(
+            "ART-2: Second",
+            "dev@example.com",
+            Utc.with_ymd_and_hms(2026, 9, 15, 8, 0, 0).unwrap(),
+        );
+        let credentials = GitCredentials::default();
+        fetch(&local.repo, "origin", "develop", &FetchContext::quiet(&credentials)).unwrap();
+
+        assert_eq!(head_of(&local.repo, "refs/remotes/origin/develop"), second);
+        assert_eq!(head_of(&local.repo, "refs/heads/develop"), first);
+        assert_eq!(head_of(&local.repo, "HEAD"), first);
+        assert_eq!(std::fs::read_to_string(&dirty).unwrap(), "uncommitted");
+        assert_eq!(branch_tip(&local.repo, "origin", "develop").unwrap(), second);
+    }
+
+    #[test]
+    fn branch_tip_falls_back_to_the_local_branch() {
+        let repo = FixtureRepo::new();
+        let first = repo.commit(
+            "ART-1: First",
+            "dev@example.com",
+            Utc.with_ymd_and_hms(2026, 9, 14, 8, 0, 0).unwrap(),
+        );
+
+        assert_eq!(branch_tip(&repo.repo, "origin", "develop").unwrap(), first);
+        assert!(branch_tip(&repo.repo, "origin", "main").is_err());
+    }
+
+    #[test]
+    fn sync_all_reports_failed_repos() {
+        let origin = FixtureRepo::new();
+        origin.commit(
+            "ART-1: First",
+            "dev@example.com",
+            Utc.with_ymd_and_hms(2026, 9, 14, 8, 0, 0).unwrap(),
+        );
+        let good = FixtureRepo::clone_of(&origin);
+        let second = origin.commit(
+            "ART-2: Second",
+            "dev@example.com",
+            Utc.with_ymd_and_hms(2026, 9, 15, 8, 0, 0).unwrap(),
+        );
+
+        let broken = FixtureRepo::new();
+        broken.repo.remote("origin", "/nonexistent/tempo/repo").unwrap();
+
+        let develop = String::from("develop");
+        let repos = vec![
+            (good.path().to_path_buf(), develop.clone()),
+            (broken.path().to_path_buf(), develop),
+        ];
+        let failed = sync_all(
+            &repos,
+            "origin",
+            GitSync::Fetch,
+            &GitCredentials::default(),
+            Duration::from_secs(30),
+        );
+
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, broken.path());
+        assert_eq!(head_of(&good.repo, "refs/remotes/origin/develop"), second);
+    }
+
+    #[test]
+    fn mirrors_are_bare_and_read_like_clones() {
+        let origin = FixtureRepo::new();
+        let tip = origin.commit(
+            "ART-1: First",
+            "dev@example.com",
+            Utc.with_ymd_and_hms(2026, 9, 14, 8, 0, 0).unwrap(),
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("infra.git");
+        let credentials = GitCredentials::default();
+
+        let url = origin.path().to_str().unwrap();
+        create_mirror(&path, url, "origin", "develop", &FetchContext::quiet(&credentials)).unwrap();
+        let mirror = Repository::open(&path).unwrap();
+
+        assert!(mirror.is_bare());
+        assert_eq!(branch_tip(&mirror, "origin", "develop").unwrap(), tip);
+        let quiet = FetchContext::quiet(&credentials);
+        sync(&mirror, "origin", "develop", GitSync::Pull, &quiet).unwrap();
+    }
+
+    #[test]
+    fn finds_repos_up_to_the_depth() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in [
+            "api",
+            "group/web",
+            "group/web/vendor/lib",
+            ".cache/tool",
+            "a/b/c/too-deep",
+        ] {
+            Repository::init(root.path().join(dir)).unwrap();
+        }
+        std::fs::create_dir_all(root.path().join("notes")).unwrap();
+
+        let found = find_repos(root.path(), 3);
+
+        assert_eq!(found, [root.path().join("api"), root.path().join("group/web")]);
+    }
+
+    #[test]
+    fn counts_only_recent_commits_of_the_user() {
+        let repo = FixtureRepo::new();
+        let at = |day| Utc.with_ymd_and_hms(2026, 9, day, 8, 0, 0).unwrap();
+        repo.commit("ART-1: Old", "dev@example.com", at(1));
+        repo.commit("ART-2: Someone else", "other@example.com", at(10));
+        let tip = repo.commit("ART-3: Recent", "dev@example.com", at(11));
+
+        let count = count_commits_since(&repo.repo, tip, at(5).timestamp(), |email| email == "dev@example.com");
+
+        assert_eq!(count.unwrap(), 1);
+    }
+
+    #[test]
+    fn counting_walks_past_commits_with_older_times() {
+        let repo = FixtureRepo::new();
+        let at = |day| Utc.with_ymd_and_hms(2026, 9, day, 8, 0, 0).unwrap();
+        repo.commit("ART-1: In range", "dev@example.com", at(8));
+        repo.commit("ART-2: Wrong clock", "dev@example.com", at(3));
+        let tip = repo.commit("ART-3: Latest", "dev@example.com", at(10));
+
+        let count = count_commits_since(&repo.repo, tip, at(5).timestamp(), |email| email == "dev@example.com");
+
+        assert_eq!(count.unwrap(), 2);
+    }
+}
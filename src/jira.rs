@@ -1,170 +1,722 @@
-use crate::{
-    data::{ConfigFile, JiraTimeEntry},
-    pretty_print,
-};
+use crate::data::{ConfigFile, JiraTimeEntry};
 use anyhow::Context;
-use chrono::{DateTime, Duration, Local, NaiveDateTime};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone};
 use reqwest::blocking::Response;
-use serde::Serialize;
 use serde_json::json;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+const MAX_RETRIES: u32 = 5;
+/// Keys per `key in (...)` search, more would make the url too long for some servers
+const KEYS_PER_SEARCH: usize = 100;
 
-#[derive(Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct AddWorkLogPayload {
-    started: String,
-    time_spent_seconds: i64,
+/// Shared connection to jira, cheap to clone and safe to use from several threads
+#[derive(Clone)]
+pub struct JiraClient {
+    client: reqwest::blocking::Client,
+    api_url: String,
+    token: String,
 }
 
-pub fn publish_entry(config: &ConfigFile, entry: &JiraTimeEntry) -> anyhow::Result<Response> {
-    let jira_token = config.user_data.inner().get_jira_token();
-    let client = reqwest::blocking::Client::new();
+/// The user the token belongs to
+#[derive(Debug)]
+pub struct JiraUser {
+    pub key: String,
+    pub username: String,
+}
 
-    let json = json!({
-      "comment": entry.comment,
-      "started": format_date(&entry.started),
-      "timeSpentSeconds": entry.time_spent.num_seconds(),
-    })
-    .to_string();
-
-    return Ok(client
-        .post(format!(
-            "https://jira.internetbrands.com/rest/api/2/issue/{}/worklog?adjustEstimate=leave",
-            entry.ticket_id.to_str(),
-        ))
-        .body(json)
-        .header("Authorization", format!("Bearer {}", jira_token))
-        .header("Accept", "application/json")
-        .header("Content-Type", "application/json")
-        .send()?);
+#[derive(Debug)]
+pub struct Worklog {
+    pub id: String,
+    pub issue_id: String,
+    pub issue_key: String,
+    pub started: DateTime<FixedOffset>,
+    pub time_spent: Duration,
+    pub comment: String,
 }
-// TODO: Remove all this logic from this file, seperate all calls to jira into a seperate functions and call them from the delete command
-pub fn delete_worklogs(
-    config: &mut ConfigFile,
-    start_time: NaiveDateTime,
-    end_time: NaiveDateTime,
-) -> anyhow::Result<()> {
-    config.user_data.initialize();
-    let jira_token = config.user_data.inner().get_jira_token();
-    let client = reqwest::blocking::Client::new();
-
-    let user = client
-        .get("https://jira.internetbrands.com/rest/api/2/myself?fields=username")
-        .header("Accept", "application/json")
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", jira_token))
-        .send()?
-        .json::<serde_json::Value>()?;
-    let user = user.as_object().unwrap();
-
-    let me_key = user.get("key").unwrap().as_str().unwrap();
-    let me_username = user
-        .get("self")
-        .unwrap()
-        .as_str()
-        .unwrap()
-        .split("username=")
-        .nth(1)
-        .unwrap();
-
-    let search_url = format!(
-        r#"https://jira.internetbrands.com/rest/api/2/search?fields=worklog,maxResults=1000&jql=worklogDate >= "{}" and worklogDate < "{}" and (worklogAuthor in ("{}"))&startAt=0"#,
-        start_time.date().format("%Y-%m-%d"),
-        end_time.date().format("%Y-%m-%d"),
-        me_username,
-    );
-
-    let mut total_time_spent = Duration::zero();
-
-    let response = client
-        .get(search_url)
-        .header("Accept", "application/json")
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", jira_token))
-        .send()?
-        .json::<serde_json::Value>()?;
-
-    let issues = response
-        .as_object()
-        .context("Invalid json")?
-        .get("issues")
-        .context("No issues key")?
-        .as_array()
-        .context("Not an array")?;
-
-    let mut worklog_ids = vec![];
-
-    for issue in issues {
-        let issue = issue.as_object().unwrap();
-        let worklogs = issue
-            .get("fields")
-            .unwrap()
-            .as_object()
-            .unwrap()
-            .get("worklog")
-            .unwrap()
-            .as_object()
-            .unwrap()
-            .get("worklogs")
-            .unwrap()
-            .as_array()
-            .unwrap();
 
-        for worklog in worklogs {
-            let worklog = worklog.as_object().unwrap();
-            let worklog_author_key = worklog
-                .get("author")
-                .unwrap()
-                .as_object()
-                .unwrap()
-                .get("key")
-                .unwrap()
-                .as_str()
-                .unwrap();
-
-            if worklog_author_key != me_key {
-                continue;
-            }
+#[derive(Debug)]
+pub struct PublishOutcome {
+    pub status: Option<u16>,
+    pub worklog_id: Option<String>,
+    /// Timed out after the request went out, the worklog may exist in jira
+    pub unknown: bool,
+    pub error: Option<String>,
+}
 
-            total_time_spent =
-                total_time_spent + Duration::seconds(worklog.get("timeSpentSeconds").unwrap().as_i64().unwrap());
+impl PublishOutcome {
+    pub fn is_success(&self) -> bool {
+        self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
 
-            let worklog_id = worklog.get("id").unwrap().as_str().unwrap();
-            let worklog_issue_id = worklog.get("issueId").unwrap().as_str().unwrap();
+impl JiraClient {
+    pub fn new(config: &ConfigFile) -> Self {
+        let user_data = config.user_data.inner();
+        Self::with_base_url(user_data.get_jira_url(), user_data.get_jira_token())
+    }
 
-            worklog_ids.push((worklog_id, worklog_issue_id));
+    /// `base_url` is the jira root, eg. https://jira.example.com
+    pub fn with_base_url(base_url: &str, token: &str) -> Self {
+        Self {
+            client: reqwest::blocking::Client::new(),
+            api_url: format!("{}/rest/api/2", base_url.trim_end_matches('/')),
+            token: token.to_string(),
         }
     }
 
-    println!("Total time spent: {}", total_time_spent.num_hours());
-
-    let pb = pretty_print::get_progress_bar(worklog_ids.len() as u64);
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.api_url, path)
+    }
 
-    for (worklog_id, issue_id) in worklog_ids {
-        pb.inc(1);
-        let response = client
-            .delete(format!(
-                "https://jira.internetbrands.com/rest/api/2/issue/{}/worklog/{}",
-                issue_id, worklog_id
-            ))
-            .header("Authorization", format!("Bearer {}", jira_token))
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::blocking::RequestBuilder {
+        self.client
+            .request(method, url)
+            .header("Authorization", format!("Bearer {}", self.token))
             .header("Accept", "application/json")
             .header("Content-Type", "application/json")
-            .send()?;
+    }
+
+    /// Sends the request, retrying with exponential backoff on 429, 5xx and connection errors.
+    /// Retry-After is honored when jira sends it. A POST is only resent when jira can't have acted on it
+    /// (connection refused, 429 or 503), so a timeout or a 500 never creates the same worklog twice.
+    fn send_with_retry(&self, build: impl Fn() -> reqwest::blocking::RequestBuilder) -> anyhow::Result<Response> {
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let request = build().build()?;
+            let idempotent = request.method() != reqwest::Method::POST;
+            let res = self.client.execute(request);
+
+            let wait = match &res {
+                Ok(response) if should_retry(response.status(), idempotent) => {
+                    retry_after(response).unwrap_or_else(|| backoff(attempt))
+                }
+                Err(err) if err.is_connect() || (idempotent && err.is_timeout()) => backoff(attempt),
+                Err(err) if err.is_timeout() => {
+                    return Err(res.unwrap_err()).context("Timed out, jira may or may not have saved it");
+                }
+                _ => return Ok(res?),
+            };
+
+            if attempt > MAX_RETRIES {
+                return Ok(res?);
+            }
+
+            std::thread::sleep(wait);
+        }
+    }
 
+    pub fn publish_entry(&self, entry: &JiraTimeEntry) -> anyhow::Result<Response> {
+        let json = json!({
+          "comment": entry.comment,
+          "started": format_date(&entry.started),
+          "timeSpentSeconds": entry.time_spent.num_seconds(),
+        })
+        .to_string();
+
+        let url = self.url(&format!(
+            "issue/{}/worklog?adjustEstimate=leave",
+            entry.ticket_id.to_str()
+        ));
+
+        self.send_with_retry(|| self.request(reqwest::Method::POST, &url).body(json.clone()))
+    }
+
+    pub fn delete_worklog(&self, issue: &str, worklog_id: &str) -> anyhow::Result<Response> {
+        let url = self.url(&format!("issue/{}/worklog/{}?adjustEstimate=leave", issue, worklog_id));
+
+        self.send_with_retry(|| self.request(reqwest::Method::DELETE, &url))
+    }
+
+    pub fn myself(&self) -> anyhow::Result<JiraUser> {
+        let url = self.url("myself");
+        let response = self.send_with_retry(|| self.request(reqwest::Method::GET, &url))?;
         if !response.status().is_success() {
-            println!("Failed to delete worklog: {}", response.text()?);
+            anyhow::bail!("Failed to fetch the current user {}", response.status());
+        }
+
+        let user = response.json::<serde_json::Value>()?;
+        let username = user["self"]
+            .as_str()
+            .and_then(|s| s.split("username=").nth(1))
+            .context("No username in user")?;
+
+        Ok(JiraUser {
+            key: user["key"].as_str().context("No key in user")?.to_string(),
+            username: username.to_string(),
+        })
+    }
+
+    /// Worklogs of `user` started between `start` and `end` (inclusive), in the timezone jira reports them in
+    pub fn find_worklogs(&self, user: &JiraUser, start: NaiveDate, end: NaiveDate) -> anyhow::Result<Vec<Worklog>> {
+        let jql = format!(
+            r#"worklogDate >= "{}" and worklogDate <= "{}" and worklogAuthor in ("{}")"#,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d"),
+            user.username,
+        );
+
+        let issues = self.search(&[("jql", jql.as_str()), ("fields", "worklog")])?;
+
+        let mut res = vec![];
+        for issue in issues.iter() {
+            let key = issue["key"].as_str().context("Issue without key")?;
+            let embedded = &issue["fields"]["worklog"];
+            let mut worklogs = embedded["worklogs"]
+                .as_array()
+                .context("Issue without worklogs")?
+                .clone();
+
+            // Search only embeds the first 20 worklogs of an issue
+            if embedded["total"]
+                .as_u64()
+                .is_some_and(|total| total as usize > worklogs.len())
+            {
+                worklogs = self.issue_worklogs(key)?;
+            }
+
+            for worklog in worklogs {
+                if worklog["author"]["key"].as_str() != Some(user.key.as_str()) {
+                    continue;
+                }
+
+                let started = worklog["started"].as_str().context("Worklog without start")?;
+                let started = DateTime::parse_from_str(started, "%Y-%m-%dT%H:%M:%S%.3f%z")?;
+
+                // The issue matched, but other worklogs on it can be outside of the range
+                if started.date_naive() < start || started.date_naive() > end {
+                    continue;
+                }
+
+                res.push(Worklog {
+                    id: worklog["id"].as_str().context("Worklog without id")?.to_string(),
+                    issue_id: worklog["issueId"]
+                        .as_str()
+                        .context("Worklog without issue")?
+                        .to_string(),
+                    issue_key: key.to_string(),
+                    started,
+                    time_spent: Duration::seconds(worklog["timeSpentSeconds"].as_i64().unwrap_or(0)),
+                    comment: worklog["comment"].as_str().unwrap_or("").to_string(),
+                });
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Looks up `keys` with a JQL search per `KEYS_PER_SEARCH` keys, reading every page of each.
+    /// Keys that don't exist are missing from the result
+    pub fn find_issues(&self, keys: &[String]) -> anyhow::Result<Vec<IssueInfo>> {
+        let mut issues = vec![];
+        for chunk in keys.chunks(KEYS_PER_SEARCH) {
+            // validateQuery=warn turns unknown keys into warnings instead of failing the whole query
+            let quoted = chunk.iter().map(|k| format!("\"{}\"", k)).collect::<Vec<_>>();
+            let jql = format!("key in ({})", quoted.join(","));
+
+            issues.extend(self.search(&[
+                ("jql", jql.as_str()),
+                ("fields", "status"),
+                ("expand", "operations"),
+                ("validateQuery", "warn"),
+            ])?);
+        }
+
+        let mut res = vec![];
+        for issue in issues {
+            let status = &issue["fields"]["status"];
+            let operations = issue["operations"]["linkGroups"].as_array();
+
+            res.push(IssueInfo {
+                key: issue["key"].as_str().context("Issue without key")?.to_string(),
+                status: status["name"].as_str().unwrap_or("").to_string(),
+                closed: status["statusCategory"]["key"].as_str() == Some("done"),
+                // Without operations we can't tell, let the POST decide
+                can_log_work: operations.is_none_or(|groups| has_operation(groups, "log-work")),
+            });
+        }
+
+        Ok(res)
+    }
+
+    /// Every issue matching the query, jira caps each page at its own limit whatever maxResults says
+    fn search(&self, query: &[(&str, &str)]) -> anyhow::Result<Vec<serde_json::Value>> {
+        let url = self.url("search");
+        let mut issues = vec![];
+
+        loop {
+            let start_at = issues.len().to_string();
+            let response = self.send_with_retry(|| {
+                self.request(reqwest::Method::GET, &url)
+                    .query(query)
+                    .query(&[("startAt", start_at.as_str()), ("maxResults", "1000")])
+            })?;
+
+            if !response.status().is_success() {
+                anyhow::bail!("Search failed {} {}", response.status(), response.text()?);
+            }
+
+            let response = response.json::<serde_json::Value>()?;
+            let page = response["issues"].as_array().context("No issues key")?;
+            issues.extend(page.iter().cloned());
+
+            let total = response["total"].as_u64().unwrap_or(0) as usize;
+            if page.is_empty() || issues.len() >= total {
+                return Ok(issues);
+            }
+        }
+    }
+
+    /// All worklogs of an issue, page by page
+    fn issue_worklogs(&self, key: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let url = self.url(&format!("issue/{}/worklog", key));
+        let mut worklogs = vec![];
+
+        loop {
+            let start_at = worklogs.len().to_string();
+            let response = self.send_with_retry(|| {
+                self.request(reqwest::Method::GET, &url)
+                    .query(&[("startAt", start_at.as_str()), ("maxResults", "1000")])
+            })?;
+
+            if !response.status().is_success() {
+                anyhow::bail!("Worklogs of {} failed {} {}", key, response.status(), response.text()?);
+            }
+
+            let response = response.json::<serde_json::Value>()?;
+            let page = response["worklogs"].as_array().context("No worklogs key")?;
+            worklogs.extend(page.iter().cloned());
+
+            let total = response["total"].as_u64().unwrap_or(0) as usize;
+            if page.is_empty() || worklogs.len() >= total {
+                return Ok(worklogs);
+            }
+        }
+    }
+
+    /// Publishes entries with up to `concurrency` requests in flight, outcomes are in the order of `entries`.
+    /// `on_done` gets the index of each entry as soon as its outcome is known.
+    pub fn publish_all(
+        &self,
+        entries: &[JiraTimeEntry],
+        concurrency: usize,
+        on_done: impl Fn(usize, &JiraTimeEntry, &PublishOutcome) + Sync,
+    ) -> Vec<PublishOutcome> {
+        let next = AtomicUsize::new(0);
+        let outcomes = Mutex::new((0..entries.len()).map(|_| None).collect::<Vec<_>>());
+
+        std::thread::scope(|scope| {
+            for _ in 0..concurrency.clamp(1, entries.len().max(1)) {
+                scope.spawn(|| loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let entry = match entries.get(idx) {
+                        Some(val) => val,
+                        None => break,
+                    };
+
+                    let outcome = match self.publish_entry(entry) {
+                        Ok(res) => {
+                            let status = res.status();
+                            let body = res.text().unwrap_or_default();
+
+                            if status.is_success() {
+                                let json = serde_json::from_str::<serde_json::Value>(&body).unwrap_or_default();
+                                PublishOutcome {
+                                    status: Some(status.as_u16()),
+                                    worklog_id: json["id"].as_str().map(|id| id.to_string()),
+                                    unknown: false,
+                                    error: None,
+                                }
+                            } else {
+                                PublishOutcome {
+                                    status: Some(status.as_u16()),
+                                    worklog_id: None,
+                                    unknown: false,
+                                    error: Some(body),
+                                }
+                            }
+                        }
+                        Err(err) => PublishOutcome {
+                            status: None,
+                            worklog_id: None,
+                            unknown: err.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout()),
+                            error: Some(format!("{err:#}")),
+                        },
+                    };
+
+                    on_done(idx, entry, &outcome);
+                    outcomes.lock().unwrap()[idx] = Some(outcome);
+                });
+            }
+        });
+
+        outcomes.into_inner().unwrap().into_iter().map(|o| o.unwrap()).collect()
+    }
+}
+
+/// Requests that aren't idempotent are only retried when jira rejected them without doing anything
+fn should_retry(status: reqwest::StatusCode, idempotent: bool) -> bool {
+    match idempotent {
+        true => status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+        false => status == reqwest::StatusCode::TOO_MANY_REQUESTS || status == reqwest::StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+/// Retry-After in seconds, jira doesn't send the http-date form
+fn retry_after(response: &Response) -> Option<std::time::Duration> {
+    let seconds = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(std::time::Duration::from_secs(seconds))
+}
+
+/// 0.5s, 1s, 2s... with up to 25% jitter so parallel requests don't retry in lockstep
+fn backoff(attempt: u32) -> std::time::Duration {
+    let base = 500 * 2_u64.pow(attempt.saturating_sub(1).min(6));
+    let jitter = rand::random::<u64>() % (base / 4 + 1);
+
+    std::time::Duration::from_millis(base + jitter)
+}
+
+/// First thing that looks like a jira key, eg. ART-1234
+pub fn find_ticket_key(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+
+    for (start, _) in text.match_indices(|c: char| c.is_ascii_uppercase()) {
+        if start > 0 && bytes[start - 1].is_ascii_alphanumeric() {
+            continue;
+        }
+
+        let project_len = bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            .count();
+        let dash = start + project_len;
+
+        if bytes.get(dash) != Some(&b'-') {
+            continue;
+        }
+
+        let number_len = bytes[dash + 1..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let end = dash + 1 + number_len;
+
+        if number_len == 0 || bytes.get(end).is_some_and(|b| b.is_ascii_alphanumeric()) {
+            continue;
         }
+
+        return Some(text[start..end].to_string());
     }
 
-    return Ok(());
+    None
 }
 
-fn format_date(date: &DateTime<Local>) -> String {
-    let started = date.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
-    let plus_idx = started.find('+').unwrap();
+#[derive(Debug)]
+pub struct IssueInfo {
+    pub key: String,
+    pub status: String,
+    pub closed: bool,
+    pub can_log_work: bool,
+}
 
-    let first_part = &started[0..plus_idx];
-    let second_part = started[plus_idx..started.len()].replace(':', "");
+fn has_operation(groups: &[serde_json::Value], id: &str) -> bool {
+    groups.iter().any(|group| {
+        let links = group["links"]
+            .as_array()
+            .is_some_and(|links| links.iter().any(|l| l["id"] == id));
+        let nested = group["groups"]
+            .as_array()
+            .is_some_and(|groups| has_operation(groups, id));
+
+        links || nested
+    })
+}
 
-    return format!("{}{}", first_part, second_part);
+/// Jira wants the offset without a colon, eg. 2026-09-14T09:00:00.000-0700
+fn format_date<T: TimeZone>(date: &DateTime<T>) -> String
+where
+    T::Offset: std::fmt::Display,
+{
+    return date.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockJira, MOCK_PAGE_LIMIT, MOCK_USER};
+    use chrono::{NaiveDate, NaiveTime};
+
+    #[test]
+    fn ticket_keys_in_text() {
+        assert_eq!(find_ticket_key("Fix ART-1234: login"), Some("ART-1234".to_string()));
+        assert_eq!(find_ticket_key("see (AB2-7)."), Some("AB2-7".to_string()));
+        assert_eq!(find_ticket_key("xART-12 and ART- and ART-12x"), None);
+    }
+
+    fn started(tz: chrono_tz::Tz, y: i32, m: u32, d: u32, h: u32) -> DateTime<chrono_tz::Tz> {
+        crate::time::local_datetime(
+            tz,
+            NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+            NaiveTime::from_hms_opt(h, 0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn log_work_operation_in_nested_groups() {
+        let groups = serde_json::json!([
+            { "links": [{ "id": "edit-issue" }] },
+            { "groups": [{ "links": [{ "id": "log-work" }] }] }
+        ]);
+
+        assert!(has_operation(groups.as_array().unwrap(), "log-work"));
+        assert!(!has_operation(groups.as_array().unwrap(), "assign-issue"));
+    }
+
+    #[test]
+    fn retries_rate_limits_and_server_errors_only() {
+        assert!(should_retry(reqwest::StatusCode::TOO_MANY_REQUESTS, true));
+        assert!(should_retry(reqwest::StatusCode::BAD_GATEWAY, true));
+        assert!(!should_retry(reqwest::StatusCode::BAD_REQUEST, true));
+        assert!(!should_retry(reqwest::StatusCode::CREATED, true));
+    }
+
+    #[test]
+    fn posts_are_only_retried_when_jira_did_nothing() {
+        assert!(should_retry(reqwest::StatusCode::TOO_MANY_REQUESTS, false));
+        assert!(should_retry(reqwest::StatusCode::SERVICE_UNAVAILABLE, false));
+        assert!(!should_retry(reqwest::StatusCode::INTERNAL_SERVER_ERROR, false));
+        assert!(!should_retry(reqwest::StatusCode::GATEWAY_TIMEOUT, false));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially() {
+        let first = backoff(1).as_millis();
+        let third = backoff(3).as_millis();
+
+        assert!((500..=625).contains(&first));
+        assert!((2000..=2500).contains(&third));
+    }
+
+    #[test]
+    fn format_date_positive_offset() {
+        let date = started(chrono_tz::Europe::Zagreb, 2026, 9, 14, 9);
+        assert_eq!(format_date(&date), "2026-09-14T09:00:00.000+0200");
+    }
+
+    #[test]
+    fn format_date_negative_offset() {
+        let date = started(chrono_tz::America::Los_Angeles, 2026, 9, 14, 23);
+        assert_eq!(format_date(&date), "2026-09-14T23:00:00.000-0700");
+    }
+
+    #[test]
+    fn format_date_utc() {
+        let date = started(chrono_tz::UTC, 2026, 1, 5, 9);
+        assert_eq!(format_date(&date), "2026-01-05T09:00:00.000+0000");
+    }
+
+    #[test]
+    fn format_date_follows_dst() {
+        let winter = started(chrono_tz::America::New_York, 2026, 3, 6, 9);
+        let summer = started(chrono_tz::America::New_York, 2026, 3, 9, 9);
+
+        assert_eq!(format_date(&winter), "2026-03-06T09:00:00.000-0500");
+        assert_eq!(format_date(&summer), "2026-03-09T09:00:00.000-0400");
+    }
+
+    fn entry(ticket: &str, hours: i64) -> JiraTimeEntry {
+        JiraTimeEntry {
+            ticket_id: crate::data::JiraTicketId::Regular(ticket.to_string()),
+            comment: format!("Work on {}", ticket),
+            started: started(chrono_tz::Europe::Zagreb, 2026, 9, 14, 9),
+            time_spent: Duration::hours(hours),
+        }
+    }
+
+    #[test]
+    fn publish_all_creates_worklogs() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        jira.add_issue("ART-2");
+
+        let outcomes = jira
+            .client()
+            .publish_all(&[entry("ART-1", 3), entry("ART-2", 5)], 2, |_, _, _| {});
+
+        assert!(outcomes.iter().all(|o| o.is_success()));
+        let worklogs = jira.worklogs();
+        assert_eq!(worklogs.len(), 2);
+
+        let art2 = worklogs.iter().find(|w| w.issue_key == "ART-2").unwrap();
+        assert_eq!(outcomes[1].worklog_id.as_ref(), Some(&art2.id));
+        assert_eq!(art2.time_spent_seconds, 5 * 3600);
+        assert_eq!(art2.started, "2026-09-14T09:00:00.000+0200");
+        assert_eq!(art2.comment, "Work on ART-2");
+    }
+
+    #[test]
+    fn publish_all_reports_failures_per_entry() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        jira.add_issue("ART-2");
+        jira.update_issue("ART-2", |i| i.can_log_work = false);
+
+        let outcomes = jira.client().publish_all(
+            &[entry("ART-1", 1), entry("ART-2", 1), entry("ART-3", 1)],
+            1,
+            |_, _, _| {},
+        );
+
+        assert!(outcomes[0].is_success());
+        assert_eq!(outcomes[1].status, Some(400));
+        assert_eq!(outcomes[2].status, Some(404));
+        assert!(outcomes[2].error.as_ref().unwrap().contains("Issue Does Not Exist"));
+        assert_eq!(jira.worklogs().len(), 1);
+    }
+
+    #[test]
+    fn retries_server_errors_but_not_client_errors() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        let client = jira.client();
+
+        jira.fail_next(&[503, 429]);
+        assert!(client.publish_entry(&entry("ART-1", 1)).unwrap().status().is_success());
+        assert_eq!(jira.requests().len(), 3);
+
+        jira.fail_next(&[400]);
+        assert_eq!(client.publish_entry(&entry("ART-1", 1)).unwrap().status().as_u16(), 400);
+        assert_eq!(jira.requests().len(), 4);
+        assert_eq!(jira.worklogs().len(), 1);
+    }
+
+    #[test]
+    fn server_errors_on_publish_are_not_resent() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        let client = jira.client();
+
+        jira.fail_next(&[502]);
+        assert_eq!(client.publish_entry(&entry("ART-1", 1)).unwrap().status().as_u16(), 502);
+        assert_eq!(jira.requests().len(), 1);
+        assert!(jira.worklogs().is_empty());
+    }
+
+    #[test]
+    fn find_issues_reports_status_and_missing_keys() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        jira.add_issue("ART-2");
+        jira.update_issue("ART-2", |i| {
+            i.status = "Done".to_string();
+            i.closed = true;
+        });
+
+        let issues = jira
+            .client()
+            .find_issues(&["ART-1".to_string(), "ART-2".to_string(), "ART-3".to_string()])
+            .unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert!(!issues[0].closed && issues[0].can_log_work);
+        assert!(issues[1].closed);
+        assert_eq!(issues[1].status, "Done");
+    }
+
+    #[test]
+    fn find_issues_reads_every_page() {
+        let jira = MockJira::start();
+        let keys = (1..=MOCK_PAGE_LIMIT + 10)
+            .map(|n| format!("ART-{}", n))
+            .collect::<Vec<_>>();
+        keys.iter().for_each(|k| jira.add_issue(k));
+
+        let issues = jira.client().find_issues(&keys).unwrap();
+
+        assert_eq!(issues.len(), keys.len());
+        assert_eq!(jira.requests().len(), 2);
+    }
+
+    #[test]
+    fn find_issues_splits_long_key_lists() {
+        let jira = MockJira::start();
+        let keys = (1..=KEYS_PER_SEARCH + 10)
+            .map(|n| format!("ART-{}", n))
+            .collect::<Vec<_>>();
+        keys.iter().for_each(|k| jira.add_issue(k));
+
+        let issues = jira.client().find_issues(&keys).unwrap();
+
+        assert_eq!(issues.len(), keys.len());
+        // Two pages for the first chunk, one for the rest
+        assert_eq!(jira.requests().len(), 3);
+    }
+
+    #[test]
+    fn find_worklogs_only_returns_own_worklogs_in_range() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        let mine = jira.add_worklog("ART-1", MOCK_USER, "2026-09-14T09:00:00.000+0200", 3600);
+        jira.add_worklog("ART-1", MOCK_USER, "2026-08-31T09:00:00.000+0200", 3600);
+        jira.add_worklog("ART-1", "someone", "2026-09-14T09:00:00.000+0200", 3600);
+
+        let client = jira.client();
+        let me = client.myself().unwrap();
+        assert_eq!(me.username, MOCK_USER);
+
+        let day = |d| NaiveDate::from_ymd_opt(2026, 9, d).unwrap();
+        let worklogs = client.find_worklogs(&me, day(1), day(30)).unwrap();
+
+        assert_eq!(worklogs.len(), 1);
+        assert_eq!(worklogs[0].id, mine);
+        assert_eq!(worklogs[0].time_spent, Duration::hours(1));
+    }
+
+    #[test]
+    fn find_worklogs_reads_every_page_and_every_worklog() {
+        let jira = MockJira::start();
+        // More issues than fit in one search page, and one with more worklogs than search embeds
+        for n in 1..=MOCK_PAGE_LIMIT + 10 {
+            let key = format!("ART-{}", n);
+            jira.add_issue(&key);
+            jira.add_worklog(&key, MOCK_USER, "2026-09-14T09:00:00.000+0200", 600);
+        }
+        for _ in 0..MOCK_PAGE_LIMIT + 5 {
+            jira.add_worklog("ART-1", MOCK_USER, "2026-09-15T09:00:00.000+0200", 600);
+        }
+
+        let client = jira.client();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 9, d).unwrap();
+        let worklogs = client
+            .find_worklogs(&client.myself().unwrap(), day(1), day(30))
+            .unwrap();
+
+        assert_eq!(worklogs.len(), 2 * MOCK_PAGE_LIMIT + 15);
+        assert_eq!(
+            worklogs.iter().filter(|w| w.issue_key == "ART-1").count(),
+            MOCK_PAGE_LIMIT + 6
+        );
+    }
+
+    #[test]
+    fn delete_worklog_by_issue_key_or_id() {
+        let jira = MockJira::start();
+        jira.add_issue("ART-1");
+        let first = jira.add_worklog("ART-1", MOCK_USER, "2026-09-14T09:00:00.000+0200", 3600);
+        jira.add_worklog("ART-1", MOCK_USER, "2026-09-15T09:00:00.000+0200", 3600);
+
+        let client = jira.client();
+        assert_eq!(client.delete_worklog("ART-1", &first).unwrap().status().as_u16(), 204);
+        assert_eq!(client.delete_worklog("ART-1", &first).unwrap().status().as_u16(), 404);
+        assert_eq!(jira.worklogs().len(), 1);
+    }
 }
@@ -2,12 +2,16 @@
 
 extern crate core;
 
+mod calendar;
 mod commands;
 mod data;
 mod git_helpers;
 mod jira;
+mod planning;
 mod pretty_print;
 mod step;
+#[cfg(test)]
+mod testing;
 mod time;
 
 use commands::Tempo;
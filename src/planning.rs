@@ -0,0 +1,492 @@
+//! Splits the working time of a range between tickets. Nothing in here touches git, jira or the clock,
+//! the caller collects commits and the calendar.
+
+use crate::data::{
+    truncate_comment, AbsenceKind, DayAdjustments, JiraTicketId, JiraTimeEntry, WorkSchedule, DAILY_STANDUP_ID, PTO_ID,
+};
+use crate::time;
+use chrono::{DateTime, Duration, NaiveDate};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// All commits made for one ticket, `started` is the time of the first one
+#[derive(Debug, Clone)]
+pub struct TicketCommits {
+    pub ticket_id: String,
+    pub comments: Vec<String>,
+    pub started: DateTime<Tz>,
+}
+
+/// The days to plan and everything that changes them
+pub struct PlanCalendar<'a> {
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+    pub adjustments: &'a DayAdjustments,
+    pub absence_tickets: &'a HashMap<AbsenceKind, String>,
+    /// Gets the working time no commit covers, eg. when there are no commits at all
+    pub fallback_ticket: Option<&'a str>,
+    pub max_comment_length: usize,
+    pub tz: Tz,
+}
+
+/// A day with its absences, standup and meetings placed, `work` is what is left for tickets
+struct DayLayout {
+    entries: Vec<JiraTimeEntry>,
+    work_start: DateTime<Tz>,
+    work: Duration,
+}
+
+fn layout_day(calendar: &PlanCalendar, schedule: &WorkSchedule, day: NaiveDate) -> DayLayout {
+    let adjustments = calendar.adjustments;
+    let mut day_start = time::local_datetime(calendar.tz, day, schedule.start_time);
+    let mut capacity = adjustments.capacity(schedule, day);
+    let mut entries = vec![];
+
+    if adjustments.skip_days.contains(&day) {
+        entries.push(JiraTimeEntry {
+            ticket_id: JiraTicketId::Skipped,
+            started: day_start,
+            comment: String::from("empty"),
+            time_spent: Duration::zero(),
+        });
+
+        return DayLayout {
+            entries,
+            work_start: day_start,
+            work: Duration::zero(),
+        };
+    }
+
+    for absence in adjustments.absences_on(day) {
+        let absent = absence.portion.resolve(capacity);
+        if absent <= Duration::zero() {
+            continue;
+        }
+
+        let ticket = calendar
+            .absence_tickets
+            .get(&absence.kind)
+            .cloned()
+            .unwrap_or(PTO_ID.to_string());
+
+        entries.push(JiraTimeEntry {
+            ticket_id: JiraTicketId::Absence(absence.kind, ticket),
+            comment: format!("(Auto generated) {}", absence.kind),
+            started: day_start,
+            time_spent: absent,
+        });
+
+        day_start += absent;
+        capacity = capacity - absent;
+    }
+
+    let standup = schedule.standup().min(capacity).max(Duration::zero());
+    if standup > Duration::zero() {
+        entries.push(JiraTimeEntry {
+            ticket_id: JiraTicketId::DailyStandup(DAILY_STANDUP_ID.to_string()),
+            time_spent: standup,
+            comment: "(Auto generated) Daily standup".to_string(),
+            started: day_start,
+        });
+    }
+
+    let mut meetings = Duration::zero();
+    for meeting in adjustments.meetings_on(day) {
+        let spent = meeting.duration.min(capacity - standup - meetings);
+        if spent <= Duration::zero() {
+            break;
+        }
+
+        entries.push(JiraTimeEntry {
+            ticket_id: JiraTicketId::Meeting(meeting.ticket.clone()),
+            time_spent: spent,
+            comment: format!("(Auto generated) {}", meeting.summary),
+            started: meeting.started,
+        });
+        meetings = meetings + spent;
+    }
+
+    DayLayout {
+        entries,
+        work_start: day_start + standup,
+        work: (capacity - standup - meetings).max(Duration::zero()),
+    }
+}
+
+/// Splits `total` into `parts` budgets that add up to exactly `total` and differ by at most a second
+fn share(total: Duration, parts: usize) -> Vec<Duration> {
+    if parts == 0 {
+        return vec![];
+    }
+
+    let each = total.num_seconds() / parts as i64;
+    let extra = total.num_seconds() % parts as i64;
+    let mut res = (0..parts as i64)
+        .map(|idx| Duration::seconds(each + if idx < extra { 1 } else { 0 }))
+        .collect::<Vec<_>>();
+
+    // Anything below a second goes to the last ticket
+    let assigned = res.iter().fold(Duration::zero(), |acc, d| acc + *d);
+    res[parts - 1] = res[parts - 1] + total - assigned;
+
+    res
+}
+
+/// Lays out absences, standups and meetings of every working day and fills the rest with `commits`.
+/// Each ticket gets an equal share of the working time, in the order of its first commit.
+pub fn plan(commits: &[TicketCommits], calendar: &PlanCalendar, schedule: &WorkSchedule) -> Vec<JiraTimeEntry> {
+    let layouts = calendar
+        .first_day
+        .iter_days()
+        .take_while(|day| *day <= calendar.last_day)
+        .filter(|day| calendar.adjustments.capacity(schedule, *day) > Duration::zero())
+        .map(|day| layout_day(calendar, schedule, day))
+        .collect::<Vec<_>>();
+
+    let total_work = layouts.iter().fold(Duration::zero(), |acc, l| acc + l.work);
+    let mut budgets = share(total_work, commits.len());
+    let mut current = 0;
+
+    let mut res = vec![];
+    for layout in layouts {
+        res.extend(layout.entries);
+
+        let mut started = layout.work_start;
+        let mut left = layout.work;
+
+        while left > Duration::zero() && current < commits.len() {
+            let spent = left.min(budgets[current]);
+            if spent > Duration::zero() {
+                let commit = &commits[current];
+                res.push(JiraTimeEntry {
+                    ticket_id: JiraTicketId::Regular(commit.ticket_id.clone()),
+                    time_spent: spent,
+                    comment: truncate_comment(
+                        &format!("(Auto generated) \n{}", commit.comments.join("\n")),
+                        calendar.max_comment_length,
+                    ),
+                    started,
+                });
+
+                started += spent;
+                left = left - spent;
+                budgets[current] = budgets[current] - spent;
+            }
+
+            if budgets[current] <= Duration::zero() {
+                current += 1;
+            }
+        }
+
+        if let (Some(ticket), true) = (calendar.fallback_ticket, left > Duration::zero()) {
+            res.push(JiraTimeEntry {
+                ticket_id: JiraTicketId::Regular(ticket.to_string()),
+                time_spent: left,
+                comment: "(Auto generated) General development".to_string(),
+                started,
+            });
+        }
+    }
+
+    res
+}
+
+/// Working days where `entries` add up to less than expected, with the logged and the expected time
+pub fn shortfalls(
+    entries: &[JiraTimeEntry],
+    calendar: &PlanCalendar,
+    schedule: &WorkSchedule,
+) -> Vec<(NaiveDate, Duration, Duration)> {
+    calendar
+        .first_day
+        .iter_days()
+        .take_while(|day| *day <= calendar.last_day)
+        .filter_map(|day| {
+            let expected = calendar.adjustments.expected(schedule, day);
+            let logged = entries
+                .iter()
+                .filter(|e| e.started.date_naive() == day)
+                .fold(Duration::zero(), |acc, e| acc + e.time_spent);
+
+            (logged < expected).then_some((day, logged, expected))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Absence, DayPortion, Meeting};
+    use chrono::{Datelike, NaiveTime, TimeZone, Weekday};
+    use chrono_tz::Europe::Zagreb;
+    use proptest::prelude::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 9, d).unwrap()
+    }
+
+    fn commits(n: usize) -> Vec<TicketCommits> {
+        (0..n)
+            .map(|idx| TicketCommits {
+                ticket_id: format!("ART-{}", idx + 1),
+                comments: vec![format!("Commit {}", idx + 1)],
+                started: Zagreb.with_ymd_and_hms(2026, 9, 1, 10, 0, 0).unwrap(),
+            })
+            .collect()
+    }
+
+    fn run(
+        commits: &[TicketCommits],
+        first: NaiveDate,
+        last: NaiveDate,
+        adjustments: &DayAdjustments,
+    ) -> Vec<JiraTimeEntry> {
+        let tickets = HashMap::new();
+        let calendar = PlanCalendar {
+            first_day: first,
+            last_day: last,
+            adjustments,
+            absence_tickets: &tickets,
+            fallback_ticket: None,
+            max_comment_length: 1000,
+            tz: Zagreb,
+        };
+
+        plan(commits, &calendar, &WorkSchedule::default())
+    }
+
+    fn hours_of(entries: &[JiraTimeEntry], ticket: &str) -> Duration {
+        entries
+            .iter()
+            .filter(|e| e.ticket_id.to_str() == ticket)
+            .fold(Duration::zero(), |acc, e| acc + e.time_spent)
+    }
+
+    #[test]
+    fn no_commits_leaves_only_fixed_entries() {
+        let entries = run(&[], day(14), day(18), &DayAdjustments::default());
+
+        assert_eq!(entries.len(), 5);
+        assert!(entries
+            .iter()
+            .all(|e| matches!(e.ticket_id, JiraTicketId::DailyStandup(_))));
+    }
+
+    #[test]
+    fn tickets_share_days_equally() {
+        // Mon - Wed, 7.5h of work each, three tickets get 7.5h each
+        let entries = run(&commits(3), day(14), day(16), &DayAdjustments::default());
+
+        for ticket in ["ART-1", "ART-2", "ART-3"] {
+            assert_eq!(hours_of(&entries, ticket), Duration::minutes(450));
+        }
+    }
+
+    #[test]
+    fn more_tickets_than_days_split_a_day() {
+        let entries = run(&commits(2), day(14), day(14), &DayAdjustments::default());
+        let regular = entries
+            .iter()
+            .filter(|e| matches!(e.ticket_id, JiraTicketId::Regular(_)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(regular.len(), 2);
+        assert_eq!(regular[0].time_spent, Duration::minutes(225));
+        assert_eq!(regular[1].started, regular[0].started + Duration::minutes(225));
+    }
+
+    #[test]
+    fn skipped_days_start_on_their_day() {
+        let adjustments = DayAdjustments {
+            skip_days: vec![day(15)],
+            ..Default::default()
+        };
+        let entries = run(&commits(1), day(14), day(16), &adjustments);
+
+        let skipped = entries
+            .iter()
+            .find(|e| matches!(e.ticket_id, JiraTicketId::Skipped))
+            .unwrap();
+        assert_eq!(skipped.started.date_naive(), day(15));
+        assert_eq!(hours_of(&entries, "ART-1"), Duration::minutes(900));
+    }
+
+    #[test]
+    fn fallback_ticket_fills_days_without_commits() {
+        let adjustments = DayAdjustments::default();
+        let tickets = HashMap::new();
+        let calendar = PlanCalendar {
+            first_day: day(14),
+            last_day: day(20),
+            adjustments: &adjustments,
+            absence_tickets: &tickets,
+            fallback_ticket: Some("ART-999"),
+            max_comment_length: 1000,
+            tz: Zagreb,
+        };
+        let schedule = WorkSchedule::default();
+
+        let entries = plan(&[], &calendar, &schedule);
+        assert_eq!(hours_of(&entries, "ART-999"), Duration::minutes(5 * 450));
+        assert!(shortfalls(&entries, &calendar, &schedule).is_empty());
+    }
+
+    #[test]
+    fn shortfalls_list_days_under_the_schedule() {
+        let adjustments = DayAdjustments {
+            skip_days: vec![day(16)],
+            ..Default::default()
+        };
+        let tickets = HashMap::new();
+        let calendar = PlanCalendar {
+            first_day: day(14),
+            last_day: day(16),
+            adjustments: &adjustments,
+            absence_tickets: &tickets,
+            fallback_ticket: None,
+            max_comment_length: 1000,
+            tz: Zagreb,
+        };
+        let schedule = WorkSchedule::default();
+
+        let short = shortfalls(&plan(&[], &calendar, &schedule), &calendar, &schedule);
+        assert_eq!(
+            short,
+            vec![
+                (day(14), Duration::minutes(30), Duration::hours(8)),
+                (day(15), Duration::minutes(30), Duration::hours(8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn share_adds_up() {
+        let parts = share(Duration::seconds(10), 3);
+        assert_eq!(
+            parts,
+            vec![Duration::seconds(4), Duration::seconds(3), Duration::seconds(3)]
+        );
+        assert!(share(Duration::hours(1), 0).is_empty());
+    }
+
+    prop_compose! {
+        fn portion()(kind in 0..3, minutes in 0..600i64) -> DayPortion {
+            match kind {
+                0 => DayPortion::Full,
+                1 => DayPortion::Half,
+                _ => DayPortion::Custom(Duration::minutes(minutes)),
+            }
+        }
+    }
+
+    prop_compose! {
+        fn scenario()(
+            first in 0..20u32,
+            len in 1..40u32,
+            ticket_count in 0..6usize,
+            weekdays in proptest::collection::vec(0..21i64, 5),
+            standup in 0..90i64,
+            skipped in proptest::collection::vec(any::<bool>(), 60),
+            absences in proptest::collection::vec(proptest::option::weighted(0.2, portion()), 60),
+            meetings in proptest::collection::vec(proptest::collection::vec(0..300i64, 0..3), 60),
+        ) -> (NaiveDate, NaiveDate, usize, WorkSchedule, DayAdjustments) {
+            let first_day = day(1) + Duration::days(first as i64);
+            let last_day = first_day + Duration::days(len as i64 - 1);
+
+            let mut week = [0; 7];
+            for (idx, half_hours) in weekdays.iter().enumerate() {
+                week[idx] = half_hours * 30;
+            }
+            let schedule = WorkSchedule {
+                week,
+                standup_minutes: standup,
+                ..Default::default()
+            };
+
+            let days = first_day.iter_days().take(len as usize).collect::<Vec<_>>();
+            let adjustments = DayAdjustments {
+                skip_days: days.iter().zip(skipped.iter()).filter(|(_, s)| **s).map(|(d, _)| *d).collect(),
+                absences: days
+                    .iter()
+                    .zip(absences.iter())
+                    .filter_map(|(d, a)| a.map(|portion| Absence { date: *d, kind: AbsenceKind::Pto, portion }))
+                    .collect(),
+                meetings: days
+                    .iter()
+                    .zip(meetings.iter())
+                    .flat_map(|(d, minutes)| {
+                        minutes.iter().enumerate().map(|(idx, m)| Meeting {
+                            summary: format!("Meeting {}", idx),
+                            ticket: "ART-500".to_string(),
+                            started: time::local_datetime(
+                                Zagreb,
+                                *d,
+                                NaiveTime::from_hms_opt(10 + idx as u32, 0, 0).unwrap(),
+                            ),
+                            duration: Duration::minutes(*m),
+                        })
+                    })
+                    .collect(),
+                hours_overrides: vec![],
+            };
+
+            (first_day, last_day, ticket_count, schedule, adjustments)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn plan_invariants(
+            (first_day, last_day, ticket_count, schedule, adjustments) in scenario(),
+            fallback in proptest::option::of(Just("ART-999")),
+        ) {
+            let tickets = HashMap::new();
+            let calendar = PlanCalendar {
+                first_day,
+                last_day,
+                adjustments: &adjustments,
+                absence_tickets: &tickets,
+                fallback_ticket: fallback,
+                max_comment_length: 1000,
+                tz: Zagreb,
+            };
+            let commits = commits(ticket_count);
+            let entries = plan(&commits, &calendar, &schedule);
+
+            for entry in entries.iter() {
+                let day = entry.started.date_naive();
+                prop_assert!(day >= first_day && day <= last_day);
+                prop_assert!(!matches!(day.weekday(), Weekday::Sat | Weekday::Sun));
+
+                if !matches!(entry.ticket_id, JiraTicketId::Skipped) {
+                    prop_assert!(entry.time_spent > Duration::zero());
+                }
+            }
+
+            // Every working day is filled up to its capacity, never over it
+            for day in first_day.iter_days().take_while(|d| *d <= last_day) {
+                let logged = entries
+                    .iter()
+                    .filter(|e| e.started.date_naive() == day)
+                    .fold(Duration::zero(), |acc, e| acc + e.time_spent);
+                let expected = adjustments.expected(&schedule, day);
+
+                if commits.is_empty() && fallback.is_none() {
+                    prop_assert!(logged <= expected);
+                } else {
+                    prop_assert_eq!(logged, expected, "day {}", day);
+                }
+            }
+
+            if !commits.is_empty() || fallback.is_some() {
+                prop_assert!(shortfalls(&entries, &calendar, &schedule).is_empty());
+            }
+
+            // Tickets get the same time, give or take the rounding of a second
+            let shares = commits.iter().map(|c| hours_of(&entries, &c.ticket_id)).collect::<Vec<_>>();
+            if let (Some(min), Some(max)) = (shares.iter().min(), shares.iter().max()) {
+                prop_assert!(*max - *min <= Duration::seconds(1));
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::Duration;
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 
@@ -15,3 +16,39 @@ pub fn get_progress_bar(len: u64) -> ProgressBar {
 
     return pb;
 }
+
+/// Spinner with a transfer bar for fetching one repo, `name` is shown in front
+pub fn get_fetch_bar(name: &str) -> ProgressBar {
+    let pb = ProgressBar::new(0);
+    let style = ProgressStyle::with_template("{spinner} {prefix:.bold} {bar:20} {pos}/{len} {wide_msg}")
+        .unwrap()
+        .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ");
+    pb.set_style(style);
+    pb.set_prefix(name.to_string());
+    pb.enable_steady_tick(std::time::Duration::from_millis(120));
+
+    return pb;
+}
+
+/// Hours as a decimal, eg. 7.5
+pub fn format_hours(duration: Duration) -> String {
+    let hours = duration.num_minutes() as f64 / 60.0;
+
+    format!("{:.2}", hours)
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_hours_decimals() {
+        assert_eq!(format_hours(Duration::hours(8)), "8");
+        assert_eq!(format_hours(Duration::minutes(450)), "7.5");
+        assert_eq!(format_hours(Duration::minutes(20)), "0.33");
+        assert_eq!(format_hours(Duration::zero()), "0");
+    }
+}
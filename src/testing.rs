@@ -0,0 +1,420 @@
+//! Test helpers: an in-process stand-in for the jira endpoints tempo uses and throwaway git repos
+
+use crate::jira::JiraClient;
+use chrono::{DateTime, Offset, TimeZone};
+use git2::{Repository, Signature};
+use serde_json::json;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tiny_http::{Method, Request, Response, Server};
+
+/// Key and username of the user the mock token belongs to
+pub const MOCK_USER: &str = "tester";
+
+/// Most results jira returns in one page, whatever maxResults asks for
+pub const MOCK_PAGE_LIMIT: usize = 50;
+
+/// Worklogs search embeds per issue, the rest only come from the issue's worklog endpoint
+const EMBEDDED_WORKLOGS: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct MockIssue {
+    pub id: String,
+    pub key: String,
+    pub status: String,
+    pub closed: bool,
+    pub can_log_work: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockWorklog {
+    pub id: String,
+    pub issue_key: String,
+    pub author: String,
+    pub started: String,
+    pub time_spent_seconds: i64,
+    pub comment: String,
+}
+
+#[derive(Default)]
+struct MockState {
+    issues: Vec<MockIssue>,
+    worklogs: Vec<MockWorklog>,
+    next_id: u32,
+    /// Statuses returned instead of handling the next requests
+    failures: Vec<u16>,
+    requests: Vec<String>,
+}
+
+impl MockState {
+    fn next_id(&mut self) -> String {
+        self.next_id += 1;
+        (10000 + self.next_id).to_string()
+    }
+
+    fn issue(&self, key_or_id: &str) -> Option<&MockIssue> {
+        self.issues
+            .iter()
+            .find(|i| i.key.eq_ignore_ascii_case(key_or_id) || i.id == key_or_id)
+    }
+}
+
+/// Jira rest api on a random local port, stopped when dropped
+pub struct MockJira {
+    url: String,
+    server: Arc<Server>,
+    state: Arc<Mutex<MockState>>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl MockJira {
+    pub fn start() -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("Failed to start mock jira"));
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let handle = {
+            let (server, state, url) = (server.clone(), state.clone(), url.clone());
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(&url, &state, request);
+                }
+            })
+        };
+
+        Self {
+            url,
+            server,
+            state,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn client(&self) -> JiraClient {
+        JiraClient::with_base_url(&self.url, "mock-token")
+    }
+
+    pub fn add_issue(&self, key: &str) {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id();
+
+        state.issues.push(MockIssue {
+            id,
+            key: key.to_string(),
+            status: "In Progress".to_string(),
+            closed: false,
+            can_log_work: true,
+        });
+    }
+
+    pub fn update_issue(&self, key: &str, update: impl FnOnce(&mut MockIssue)) {
+        let mut state = self.state.lock().unwrap();
+        let issue = state.issues.iter_mut().find(|i| i.key == key).expect("No such issue");
+        update(issue);
+    }
+
+    /// Adds a worklog directly, as if logged by hand, returns its id
+    pub fn add_worklog(&self, key: &str, author: &str, started: &str, time_spent_seconds: i64) -> String {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id();
+
+        state.worklogs.push(MockWorklog {
+            id: id.clone(),
+            issue_key: key.to_string(),
+            author: author.to_string(),
+            started: started.to_string(),
+            time_spent_seconds,
+            comment: String::new(),
+        });
+
+        id
+    }
+
+    pub fn worklogs(&self) -> Vec<MockWorklog> {
+        self.state.lock().unwrap().worklogs.clone()
+    }
+
+    /// Makes the next requests fail with `statuses`, in order
+    pub fn fail_next(&self, statuses: &[u16]) {
+        self.state.lock().unwrap().failures.extend_from_slice(statuses);
+    }
+
+    /// "METHOD path" of every request received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockJira {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn handle(base_url: &str, state: &Mutex<MockState>, mut request: Request) {
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+
+    let url = reqwest::Url::parse(&format!("{}{}", base_url, request.url())).unwrap();
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.to_string())
+            .unwrap_or_default()
+    };
+    let segments = url
+        .path()
+        .trim_start_matches("/rest/api/2/")
+        .split('/')
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+    let segments = segments.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+
+    let mut state = state.lock().unwrap();
+    state.requests.push(format!("{} {}", request.method(), url.path()));
+
+    if !state.failures.is_empty() {
+        let status = state.failures.remove(0);
+        let body = json!({ "errorMessages": ["Injected failure"] }).to_string();
+        let retry_after = "Retry-After: 0".parse::<tiny_http::Header>().unwrap();
+        let _ = request.respond(
+            Response::from_string(body)
+                .with_status_code(status)
+                .with_header(retry_after),
+        );
+        return;
+    }
+
+    let (status, res) = match (request.method(), segments.as_slice()) {
+        (Method::Get, ["myself"]) => (
+            200,
+            json!({
+                "key": MOCK_USER,
+                "name": MOCK_USER,
+                "self": format!("{}/rest/api/2/user?username={}", base_url, MOCK_USER),
+            }),
+        ),
+        (Method::Get, ["search"]) => match search(&state, &query("jql")) {
+            Ok(issues) => {
+                let (total, issues) = page(issues, &query("startAt"), &query("maxResults"));
+                (200, json!({ "total": total, "issues": issues }))
+            }
+            Err(res) => (400, res),
+        },
+        (Method::Get, ["issue", key, "worklog"]) => match state.issue(key) {
+            Some(issue) => {
+                let worklogs = state
+                    .worklogs
+                    .iter()
+                    .filter(|w| w.issue_key == issue.key)
+                    .map(|w| worklog_json(issue, w))
+                    .collect::<Vec<_>>();
+                let (total, worklogs) = page(worklogs, &query("startAt"), &query("maxResults"));
+                (200, json!({ "total": total, "worklogs": worklogs }))
+            }
+            None => (404, json!({ "errorMessages": ["Issue Does Not Exist"] })),
+        },
+        (Method::Post, ["issue", key, "worklog"]) => {
+            let payload = serde_json::from_str::<serde_json::Value>(&body).unwrap_or_default();
+
+            match state.issue(key).cloned() {
+                None => (404, json!({ "errorMessages": ["Issue Does Not Exist"] })),
+                Some(issue) if !issue.can_log_work => {
+                    (400, json!({ "errorMessages": ["You can't log work on this issue"] }))
+                }
+                Some(issue) => {
+                    let worklog = MockWorklog {
+                        id: state.next_id(),
+                        issue_key: issue.key.clone(),
+                        author: MOCK_USER.to_string(),
+                        started: payload["started"].as_str().unwrap_or("").to_string(),
+                        time_spent_seconds: payload["timeSpentSeconds"].as_i64().unwrap_or(0),
+                        comment: payload["comment"].as_str().unwrap_or("").to_string(),
+                    };
+                    let res = worklog_json(&issue, &worklog);
+                    state.worklogs.push(worklog);
+
+                    (201, res)
+                }
+            }
+        }
+        (Method::Delete, ["issue", key, "worklog", id]) => {
+            let issue_key = state.issue(key).map(|i| i.key.clone());
+            let before = state.worklogs.len();
+            state
+                .worklogs
+                .retain(|w| Some(&w.issue_key) != issue_key.as_ref() || w.id != *id);
+
+            if state.worklogs.len() < before {
+                (204, json!(null))
+            } else {
+                (404, json!({ "errorMessages": ["Worklog Does Not Exist"] }))
+            }
+        }
+        _ => (404, json!({ "errorMessages": ["Unknown endpoint"] })),
+    };
+
+    respond(request, status, res)
+}
+
+fn respond(request: Request, status: u16, body: serde_json::Value) {
+    let body = if body.is_null() {
+        String::new()
+    } else {
+        body.to_string()
+    };
+    let header = "Content-Type: application/json".parse::<tiny_http::Header>().unwrap();
+    let _ = request.respond(Response::from_string(body).with_status_code(status).with_header(header));
+}
+
+fn worklog_json(issue: &MockIssue, worklog: &MockWorklog) -> serde_json::Value {
+    json!({
+        "id": worklog.id,
+        "issueId": issue.id,
+        "author": { "key": worklog.author, "name": worklog.author },
+        "started": worklog.started,
+        "timeSpentSeconds": worklog.time_spent_seconds,
+        "comment": worklog.comment,
+    })
+}
+
+/// Understands the two queries tempo sends: `key in (..)` and `worklogDate >= .. and worklogDate <= .. and worklogAuthor in (..)`
+fn search(state: &MockState, jql: &str) -> Result<Vec<serde_json::Value>, serde_json::Value> {
+    let quoted = jql.split('"').skip(1).step_by(2).collect::<Vec<_>>();
+
+    if jql.starts_with("key in") {
+        let issues = state
+            .issues
+            .iter()
+            .filter(|i| quoted.iter().any(|k| k.eq_ignore_ascii_case(&i.key)))
+            .map(|i| {
+                let operations = if i.can_log_work {
+                    json!([{ "id": "log-work" }])
+                } else {
+                    json!([])
+                };
+
+                json!({
+                    "id": i.id,
+                    "key": i.key,
+                    "fields": {
+                        "status": {
+                            "name": i.status,
+                            "statusCategory": { "key": if i.closed { "done" } else { "indeterminate" } },
+                        },
+                    },
+                    "operations": { "linkGroups": [{ "groups": [{ "links": operations }] }] },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        return Ok(issues);
+    }
+
+    let (from, to, author) = match quoted.as_slice() {
+        [from, to, author] => (*from, *to, *author),
+        _ => return Err(json!({ "errorMessages": ["Unsupported jql"] })),
+    };
+
+    // Like jira, an issue matches when any of the author's worklogs is in range and all of its worklogs are returned
+    let issues = state
+        .issues
+        .iter()
+        .filter(|i| {
+            state.worklogs.iter().any(|w| {
+                let day = &w.started[..10];
+                w.issue_key == i.key && w.author == author && day >= from && day <= to
+            })
+        })
+        .map(|i| {
+            let worklogs = state
+                .worklogs
+                .iter()
+                .filter(|w| w.issue_key == i.key)
+                .map(|w| worklog_json(i, w))
+                .collect::<Vec<_>>();
+            let total = worklogs.len();
+            let worklogs = worklogs.into_iter().take(EMBEDDED_WORKLOGS).collect::<Vec<_>>();
+
+            json!({
+                "id": i.id,
+                "key": i.key,
+                "fields": { "worklog": { "total": total, "worklogs": worklogs } },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(issues)
+}
+
+/// Total and the requested page of `items`, with jira's cap on the page size
+fn page(items: Vec<serde_json::Value>, start_at: &str, max_results: &str) -> (usize, Vec<serde_json::Value>) {
+    let total = items.len();
+    let start_at = start_at.parse::<usize>().unwrap_or(0);
+    let max_results = max_results
+        .parse::<usize>()
+        .unwrap_or(MOCK_PAGE_LIMIT)
+        .min(MOCK_PAGE_LIMIT);
+
+    (total, items.into_iter().skip(start_at).take(max_results).collect())
+}
+
+/// A git repo in a temp dir with a `develop` branch, deleted when dropped
+pub struct FixtureRepo {
+    pub dir: tempfile::TempDir,
+    pub repo: Repository,
+}
+
+impl FixtureRepo {
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/develop").unwrap();
+
+        Self { dir, repo }
+    }
+
+    /// Clone of `origin` with its develop checked out, `origin` is its remote
+    pub fn clone_of(origin: &FixtureRepo) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::clone(origin.path().to_str().unwrap(), dir.path()).unwrap();
+
+        Self { dir, repo }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Commits an empty change on top of develop
+    pub fn commit<T: TimeZone>(&self, message: &str, email: &str, time: DateTime<T>) -> git2::Oid {
+        self.commit_authored(message, email, time.clone(), time)
+    }
+
+    /// Like `commit`, with an author date other than the commit date, as rebases and cherry-picks leave
+    pub fn commit_authored<T: TimeZone, U: TimeZone>(
+        &self,
+        message: &str,
+        email: &str,
+        authored: DateTime<T>,
+        committed: DateTime<U>,
+    ) -> git2::Oid {
+        let signature = |offset: i32, timestamp: i64| {
+            Signature::new("Dev", email, &git2::Time::new(timestamp, offset / 60)).unwrap()
+        };
+        let author = signature(authored.offset().fix().local_minus_utc(), authored.timestamp());
+        let committer = signature(committed.offset().fix().local_minus_utc(), committed.timestamp());
+
+        let tree_id = self.repo.index().unwrap().write_tree().unwrap();
+        let tree = self.repo.find_tree(tree_id).unwrap();
+        let parent = self.repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+
+        self.repo
+            .commit(Some("HEAD"), &author, &committer, message, &tree, &parents)
+            .unwrap()
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt::Formatter;
 
-use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+
+use crate::data::{Absence, AbsenceKind, DayPortion, HoursOverride};
 use colored::Colorize;
 
 pub const MAX_TIME: Option<NaiveTime> = NaiveTime::from_hms_opt(23, 59, 59);
@@ -17,7 +20,131 @@ impl std::fmt::Display for MonthDisplay {
     }
 }
 
-pub fn get_date_range_from_user() -> anyhow::Result<(NaiveDateTime, NaiveDateTime)> {
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum RangePreset {
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    LastMonth,
+    Sprint,
+    LastSprint,
+}
+
+impl std::fmt::Display for RangePreset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::ThisWeek => "This week",
+            Self::LastWeek => "Last week",
+            Self::ThisMonth => "This month",
+            Self::LastMonth => "Last month",
+            Self::Sprint => "Current sprint",
+            Self::LastSprint => "Last sprint",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// First day and length in weeks of any sprint, every other sprint is derived from it
+#[derive(Debug, Clone, Copy)]
+pub struct SprintCadence {
+    pub start: NaiveDate,
+    pub weeks: u32,
+}
+
+enum PeriodChoice {
+    Month,
+    Preset(RangePreset),
+    Custom,
+}
+
+impl std::fmt::Display for PeriodChoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Month => write!(f, "Pick a month"),
+            Self::Preset(preset) => write!(f, "{}", preset),
+            Self::Custom => write!(f, "Custom range"),
+        }
+    }
+}
+
+pub fn preset_range(
+    preset: RangePreset,
+    today: NaiveDate,
+    sprint: Option<SprintCadence>,
+) -> anyhow::Result<(NaiveDate, NaiveDate)> {
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = today.with_day(1).unwrap();
+
+    let range = match preset {
+        RangePreset::ThisWeek => (week_start, week_start + Duration::days(6)),
+        RangePreset::LastWeek => (week_start - Duration::days(7), week_start - Duration::days(1)),
+        RangePreset::ThisMonth => (month_start, last_day_of_month(today.year(), today.month())),
+        RangePreset::LastMonth => {
+            let prev = month_start - Duration::days(1);
+            (prev.with_day(1).unwrap(), prev)
+        }
+        RangePreset::Sprint | RangePreset::LastSprint => {
+            let sprint = sprint.ok_or(anyhow::anyhow!(
+                "Sprint is not configured, set it with tempo configure --sprint-start <date> --sprint-weeks <n>"
+            ))?;
+            let len = Duration::weeks(sprint.weeks.max(1) as i64);
+            let elapsed = (today - sprint.start).num_days().div_euclid(len.num_days());
+            let mut start = sprint.start + Duration::days(elapsed * len.num_days());
+
+            if preset == RangePreset::LastSprint {
+                start -= len;
+            }
+
+            (start, start + len - Duration::days(1))
+        }
+    };
+
+    Ok(range)
+}
+
+pub fn to_datetime_range(start: NaiveDate, end: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+    (start.and_time(NaiveTime::MIN), end.and_time(MAX_TIME.unwrap()))
+}
+
+pub fn get_date_range_from_user(sprint: Option<SprintCadence>) -> anyhow::Result<(NaiveDateTime, NaiveDateTime)> {
+    let today = Local::now().date_naive();
+
+    let mut options = vec![
+        PeriodChoice::Month,
+        PeriodChoice::Preset(RangePreset::ThisWeek),
+        PeriodChoice::Preset(RangePreset::LastWeek),
+    ];
+    if sprint.is_some() {
+        options.push(PeriodChoice::Preset(RangePreset::Sprint));
+        options.push(PeriodChoice::Preset(RangePreset::LastSprint));
+    }
+    options.push(PeriodChoice::Custom);
+
+    let (start, end) = match inquire::Select::new("Select period", options).prompt()? {
+        PeriodChoice::Month => return get_month_from_user(),
+        PeriodChoice::Preset(preset) => preset_range(preset, today, sprint)?,
+        PeriodChoice::Custom => {
+            let start = inquire::DateSelect::new("From").with_default(today).prompt()?;
+            let end = inquire::DateSelect::new("To")
+                .with_default(today)
+                .with_min_date(start)
+                .prompt()?;
+
+            (start, end)
+        }
+    };
+
+    println!(
+        "Selected {} - {}",
+        start.to_string().bright_green(),
+        end.to_string().bright_green()
+    );
+
+    Ok(to_datetime_range(start, end))
+}
+
+fn get_month_from_user() -> anyhow::Result<(NaiveDateTime, NaiveDateTime)> {
     let now = Local::now();
     let starting_cursor = if now.month() <= 2 { 0 } else { now.month() as usize - 2 };
 
@@ -27,12 +154,11 @@ pub fn get_date_range_from_user() -> anyhow::Result<(NaiveDateTime, NaiveDateTim
         .prompt()?;
 
     let start_date = chrono::NaiveDate::from_ymd_opt(selected_year, selected_month.month, 1)
-        .ok_or(anyhow::anyhow!("Failed to parse date"))?
-        .and_time(NaiveTime::MIN);
+        .ok_or(anyhow::anyhow!("Failed to parse date"))?;
 
-    let end_date = last_day_of_month(start_date.year(), start_date.month()).and_time(MAX_TIME.unwrap());
+    let end_date = last_day_of_month(start_date.year(), start_date.month());
 
-    Ok((start_date, end_date))
+    Ok(to_datetime_range(start_date, end_date))
 }
 
 pub fn get_years(curr: i32) -> Vec<i32> {
@@ -63,7 +189,7 @@ pub fn get_day_range(
                 .with_max_date(max_date)
                 .prompt()?;
 
-            if buf.iter().any(|d| *d == res) {
+            if buf.contains(&res) {
                 println!("{}", "Already added".red());
             } else {
                 buf.push(res);
@@ -76,6 +202,161 @@ pub fn get_day_range(
     Ok(buf)
 }
 
+/// Asks for absences on top of the already known ones in `buf`
+pub fn get_absences(min_date: NaiveDate, max_date: NaiveDate, mut buf: Vec<Absence>) -> anyhow::Result<Vec<Absence>> {
+    let msg = if buf.is_empty() {
+        "Did you have any absences (vacation, sick leave...)?"
+    } else {
+        "Did you have any other absences?"
+    };
+
+    if !inquire::Confirm::new(msg).prompt()? {
+        return Ok(buf);
+    }
+
+    loop {
+        let date = inquire::DateSelect::new("Pick absence day")
+            .with_default(min_date)
+            .with_min_date(min_date)
+            .with_max_date(max_date)
+            .prompt()?;
+
+        let kind = inquire::Select::new("Absence type", AbsenceKind::all()).prompt()?;
+        let portion = match inquire::Select::new("How long?", vec!["Full day", "Half day", "Custom hours"])
+            .raw_prompt()?
+            .index
+        {
+            0 => DayPortion::Full,
+            1 => DayPortion::Half,
+            _ => DayPortion::Custom(prompt_duration("Hours absent (eg. 2h30m)")?),
+        };
+
+        if buf.iter().any(|a| a.date == date && a.kind == kind) {
+            println!("{}", "Already added".red());
+        } else {
+            buf.push(Absence { date, kind, portion });
+        }
+
+        if !inquire::Confirm::new("Add more?").prompt()? {
+            break;
+        }
+    }
+
+    Ok(buf)
+}
+
+pub fn get_hours_overrides(min_date: NaiveDate, max_date: NaiveDate) -> anyhow::Result<Vec<HoursOverride>> {
+    let mut buf: Vec<HoursOverride> = vec![];
+
+    if !inquire::Confirm::new("Did you work custom hours on any day?").prompt()? {
+        return Ok(buf);
+    }
+
+    loop {
+        let date = inquire::DateSelect::new("Pick day")
+            .with_default(min_date)
+            .with_min_date(min_date)
+            .with_max_date(max_date)
+            .prompt()?;
+        let time = prompt_duration("Hours worked (eg. 4h)")?;
+
+        buf.retain(|o| o.date != date);
+        buf.push(HoursOverride { date, time });
+
+        if !inquire::Confirm::new("Add more?").prompt()? {
+            break;
+        }
+    }
+
+    Ok(buf)
+}
+
+fn prompt_duration(msg: &str) -> anyhow::Result<Duration> {
+    let val = inquire::Text::new(msg)
+        .with_validator(|val: &str| match parse_duration(val) {
+            Ok(_) => Ok(inquire::validator::Validation::Valid),
+            Err(err) => Ok(inquire::validator::Validation::Invalid(err.to_string().into())),
+        })
+        .prompt()?;
+
+    parse_duration(&val)
+}
+
+/// Parses durations like "4h", "7.5h", "90m", "4h30m" or a plain number of hours, which add up to more than 0
+pub fn parse_duration(val: &str) -> anyhow::Result<Duration> {
+    let val = val.trim().to_lowercase();
+    if val.is_empty() {
+        anyhow::bail!("Empty duration");
+    }
+
+    let total = match val.parse::<f32>() {
+        Ok(hours) => hours_to_duration(hours)?,
+        Err(_) => sum_units(&val)?,
+    };
+
+    if total <= Duration::zero() {
+        anyhow::bail!("{} must be more than 0", val);
+    }
+    if total > Duration::hours(24) {
+        anyhow::bail!("{} is longer than a day", val);
+    }
+
+    Ok(total)
+}
+
+/// Adds up every "<amount><unit>" part of `val`
+fn sum_units(val: &str) -> anyhow::Result<Duration> {
+    let mut total = Duration::zero();
+    let mut rest = val;
+
+    while !rest.is_empty() {
+        let unit_idx = rest
+            .find(|c: char| c.is_alphabetic())
+            .ok_or(anyhow::anyhow!("Missing unit in {}, use h or m", val))?;
+        let amount = rest[..unit_idx].trim().parse::<f32>()?;
+
+        let unit = rest[unit_idx..].chars().next().unwrap();
+
+        total = total
+            + match unit {
+                'h' => hours_to_duration(amount)?,
+                'm' => minutes_to_duration(amount)?,
+                _ => anyhow::bail!("Unknown unit {} in {}, use h or m", unit, val),
+            };
+        rest = rest[unit_idx + unit.len_utf8()..].trim_start();
+    }
+
+    Ok(total)
+}
+
+fn minutes_to_duration(minutes: f32) -> anyhow::Result<Duration> {
+    if !(0.0..=24.0 * 60.0).contains(&minutes) {
+        anyhow::bail!("Minutes must be between 0 and 1440");
+    }
+
+    Ok(Duration::minutes(minutes.round() as i64))
+}
+
+fn hours_to_duration(hours: f32) -> anyhow::Result<Duration> {
+    if !(0.0..=24.0).contains(&hours) {
+        anyhow::bail!("Hours must be between 0 and 24");
+    }
+
+    Ok(Duration::minutes((hours * 60.0).round() as i64))
+}
+
+/// Parses "2026-09-12=4h" as used by --day
+pub fn parse_hours_override(val: &str) -> anyhow::Result<HoursOverride> {
+    let (date, time) = val
+        .split_once('=')
+        .ok_or(anyhow::anyhow!("Expected <date>=<hours>, eg. 2026-09-12=4h"))?;
+
+    Ok(HoursOverride {
+        date: date.trim().parse::<NaiveDate>()?,
+        time: parse_duration(time)?,
+    })
+}
+
 pub fn get_months() -> Vec<MonthDisplay> {
     vec![
         MonthDisplay {
@@ -135,3 +416,199 @@ pub fn last_day_of_month(year: i32, month: u32) -> chrono::NaiveDate {
 
     return chrono::NaiveDate::from_ymd_opt(next_year, next_month, 1).unwrap() - chrono::Duration::days(1);
 }
+
+/// Resolves a wall clock time in `tz`. Times skipped by a DST jump resolve to the end of the gap
+/// and times repeated when clocks go back resolve to the first occurrence.
+pub fn local_datetime(tz: Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Tz> {
+    let naive = date.and_time(time);
+
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(val) => val,
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        LocalResult::None => {
+            // The jump is the first second whose wall clock reaches `naive`, offsets are within a day
+            let at = |secs: i64| tz.timestamp_opt(secs, 0).unwrap();
+            let utc = naive.and_utc().timestamp();
+            let (mut before, mut after) = (utc - 24 * 3600, utc + 24 * 3600);
+            while after - before > 1 {
+                let mid = before + (after - before) / 2;
+                if at(mid).naive_local() >= naive {
+                    after = mid;
+                } else {
+                    before = mid;
+                }
+            }
+
+            at(after)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn week_presets_start_on_monday() {
+        let range = |preset, today| preset_range(preset, today, None).unwrap();
+
+        // Monday and Sunday of the same week
+        for today in [date(2026, 9, 14), date(2026, 9, 20)] {
+            assert_eq!(
+                range(RangePreset::ThisWeek, today),
+                (date(2026, 9, 14), date(2026, 9, 20))
+            );
+            assert_eq!(
+                range(RangePreset::LastWeek, today),
+                (date(2026, 9, 7), date(2026, 9, 13))
+            );
+        }
+
+        assert_eq!(
+            range(RangePreset::ThisWeek, date(2026, 1, 2)),
+            (date(2025, 12, 29), date(2026, 1, 4))
+        );
+        assert_eq!(
+            range(RangePreset::LastWeek, date(2026, 1, 2)),
+            (date(2025, 12, 22), date(2025, 12, 28))
+        );
+    }
+
+    #[test]
+    fn month_presets() {
+        let range = |preset, today| preset_range(preset, today, None).unwrap();
+
+        assert_eq!(
+            range(RangePreset::ThisMonth, date(2026, 2, 10)),
+            (date(2026, 2, 1), date(2026, 2, 28))
+        );
+        assert_eq!(
+            range(RangePreset::LastMonth, date(2026, 1, 31)),
+            (date(2025, 12, 1), date(2025, 12, 31))
+        );
+    }
+
+    #[test]
+    fn sprint_presets_follow_the_cadence() {
+        let sprint = Some(SprintCadence {
+            start: date(2026, 9, 7),
+            weeks: 2,
+        });
+        let range = |preset, today| preset_range(preset, today, sprint).unwrap();
+
+        // The sprint started on the 21st runs into October
+        assert_eq!(
+            range(RangePreset::Sprint, date(2026, 9, 28)),
+            (date(2026, 9, 21), date(2026, 10, 4))
+        );
+        assert_eq!(
+            range(RangePreset::Sprint, date(2026, 10, 4)),
+            (date(2026, 9, 21), date(2026, 10, 4))
+        );
+        assert_eq!(
+            range(RangePreset::LastSprint, date(2026, 10, 5)),
+            (date(2026, 9, 21), date(2026, 10, 4))
+        );
+        assert_eq!(
+            range(RangePreset::LastSprint, date(2026, 9, 28)),
+            (date(2026, 9, 7), date(2026, 9, 20))
+        );
+        // Days before the configured start fall into earlier sprints
+        assert_eq!(
+            range(RangePreset::Sprint, date(2026, 9, 1)),
+            (date(2026, 8, 24), date(2026, 9, 6))
+        );
+
+        assert!(preset_range(RangePreset::Sprint, date(2026, 9, 28), None).is_err());
+    }
+
+    #[test]
+    fn parse_duration_formats() {
+        assert_eq!(parse_duration("4h").unwrap(), Duration::hours(4));
+        assert_eq!(parse_duration("7.5h").unwrap(), Duration::minutes(450));
+        assert_eq!(parse_duration("90m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("4h30m").unwrap(), Duration::minutes(270));
+        assert_eq!(parse_duration("6").unwrap(), Duration::hours(6));
+        assert!(parse_duration("4x").is_err());
+        assert!(parse_duration("25h").is_err());
+        assert!(parse_duration("-30m").is_err());
+        assert!(parse_duration("4h-30m").is_err());
+        assert_eq!(parse_duration("4h0m").unwrap(), Duration::hours(4));
+        assert_eq!(parse_duration("0h30m").unwrap(), Duration::minutes(30));
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("0h").is_err());
+        assert!(parse_duration("0").is_err());
+    }
+
+    #[test]
+    fn parse_hours_override_flag() {
+        let res = parse_hours_override("2026-09-12=4h").unwrap();
+
+        assert_eq!(res.date, date(2026, 9, 12));
+        assert_eq!(res.time, Duration::hours(4));
+        assert!(parse_hours_override("2026-09-12").is_err());
+    }
+
+    #[test]
+    fn local_datetime_keeps_the_day_for_negative_offsets() {
+        let started = local_datetime(chrono_tz::America::Los_Angeles, date(2026, 9, 14), time(23, 30));
+
+        assert_eq!(started.date_naive(), date(2026, 9, 14));
+        assert_eq!(started.time(), time(23, 30));
+        assert_eq!(started.naive_utc(), date(2026, 9, 15).and_time(time(6, 30)));
+    }
+
+    #[test]
+    fn local_datetime_keeps_the_day_for_positive_offsets() {
+        let started = local_datetime(chrono_tz::Asia::Tokyo, date(2026, 9, 14), time(0, 15));
+
+        assert_eq!(started.date_naive(), date(2026, 9, 14));
+        assert_eq!(started.naive_utc(), date(2026, 9, 13).and_time(time(15, 15)));
+    }
+
+    #[test]
+    fn local_datetime_skips_forward_over_dst_gap() {
+        // Clocks jump from 02:00 to 03:00 in Zagreb
+        let started = local_datetime(chrono_tz::Europe::Zagreb, date(2026, 3, 29), time(2, 30));
+
+        assert_eq!(started.date_naive(), date(2026, 3, 29));
+        assert_eq!(started.time(), time(3, 0));
+    }
+
+    #[test]
+    fn local_datetime_resolves_to_the_end_of_the_gap() {
+        let zagreb = local_datetime(chrono_tz::Europe::Zagreb, date(2026, 3, 29), time(2, 15));
+        // Clocks jump from 02:00 to 02:30 on Lord Howe Island
+        let lord_howe = local_datetime(chrono_tz::Australia::Lord_Howe, date(2026, 10, 4), time(2, 10));
+
+        assert_eq!(zagreb.time(), time(3, 0));
+        assert_eq!(zagreb.naive_utc(), date(2026, 3, 29).and_time(time(1, 0)));
+        assert_eq!(lord_howe.time(), time(2, 30));
+    }
+
+    #[test]
+    fn local_datetime_picks_first_occurrence_when_clocks_go_back() {
+        // 01:30 happens twice in New York
+        let started = local_datetime(chrono_tz::America::New_York, date(2026, 11, 1), time(1, 30));
+
+        assert_eq!(started.time(), time(1, 30));
+        assert_eq!(started.naive_utc(), date(2026, 11, 1).and_time(time(5, 30)));
+    }
+
+    #[test]
+    fn local_datetime_uses_offset_of_the_day_itself() {
+        let winter = local_datetime(chrono_tz::Europe::Zagreb, date(2026, 3, 27), time(9, 0));
+        let summer = local_datetime(chrono_tz::Europe::Zagreb, date(2026, 3, 30), time(9, 0));
+
+        assert_eq!(winter.naive_utc().time(), time(8, 0));
+        assert_eq!(summer.naive_utc().time(), time(7, 0));
+    }
+}